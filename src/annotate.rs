@@ -0,0 +1,234 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use clap::ValueEnum;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use crate::error::{GitVoteError, Result};
+
+/// Which check produced a finding; decides the exit code when it fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Vote,
+    Chain,
+}
+
+/// A single problem found in a vote or block file.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub rule: &'static str,
+    pub scope: Scope,
+    pub message: String,
+}
+
+/// Outcome of a validation run: every file looked at, and what was wrong.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub checked: Vec<PathBuf>,
+    pub findings: Vec<Finding>,
+}
+
+impl CheckReport {
+    pub fn merge(&mut self, other: CheckReport) {
+        self.checked.extend(other.checked);
+        self.findings.extend(other.findings);
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Turns findings into the matching error category. Vote problems win
+    /// over chain problems since they are what a PR author has to fix.
+    pub fn into_result(self) -> Result<()> {
+        let votes = self.findings.iter().filter(|f| f.scope == Scope::Vote).count();
+        let blocks = self.findings.len() - votes;
+
+        if votes > 0 {
+            return Err(GitVoteError::Validation(format!("{} problem(s) found in votes", votes)));
+        }
+        if blocks > 0 {
+            return Err(GitVoteError::Chain(format!("{} problem(s) found in blocks", blocks)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable lines on stderr
+    Text,
+    /// GitHub Actions `::error` workflow commands
+    Github,
+    /// GitLab Code Quality JSON
+    Gitlab,
+    /// JUnit XML, one test case per checked file
+    Junit,
+    /// SARIF 2.1.0
+    Sarif,
+}
+
+/// Returns the 1-based line of the first occurrence of `"key"` in `content`,
+/// so annotations point at the offending field rather than the whole file.
+pub fn line_of(content: &str, key: &str) -> Option<usize> {
+    let needle = format!("\"{}\"", key);
+    content.lines().position(|l| l.contains(&needle)).map(|i| i + 1)
+}
+
+pub fn render(format: OutputFormat, report: &CheckReport) -> String {
+    match format {
+        OutputFormat::Text => render_text(report),
+        OutputFormat::Github => render_github(report),
+        OutputFormat::Gitlab => render_gitlab(report),
+        OutputFormat::Junit => render_junit(report),
+        OutputFormat::Sarif => render_sarif(report),
+    }
+}
+
+fn render_text(report: &CheckReport) -> String {
+    let mut out = String::new();
+    for f in &report.findings {
+        let _ = match f.line {
+            Some(line) => writeln!(out, "✘ {}:{}: {}", f.file.display(), line, f.message),
+            None => writeln!(out, "✘ {}: {}", f.file.display(), f.message),
+        };
+    }
+    out
+}
+
+fn render_github(report: &CheckReport) -> String {
+    let mut out = String::new();
+    for f in &report.findings {
+        let mut props = format!("file={}", escape_github_property(&path_str(&f.file)));
+        if let Some(line) = f.line {
+            let _ = write!(props, ",line={}", line);
+        }
+        let _ = write!(props, ",title={}", escape_github_property(&format!("gitvote {}", f.rule)));
+        let _ = writeln!(out, "::error {}::{}", props, escape_github_data(&f.message));
+    }
+    out
+}
+
+fn render_gitlab(report: &CheckReport) -> String {
+    let issues: Vec<_> = report
+        .findings
+        .iter()
+        .map(|f| {
+            let fingerprint = Sha256::digest(format!("{}:{}:{}", f.rule, path_str(&f.file), f.message));
+            json!({
+                "description": f.message,
+                "check_name": f.rule,
+                "fingerprint": format!("{:x}", fingerprint),
+                "severity": "major",
+                "location": {
+                    "path": path_str(&f.file),
+                    "lines": { "begin": f.line.unwrap_or(1) },
+                },
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&issues).expect("json value serializes")
+}
+
+fn render_junit(report: &CheckReport) -> String {
+    let mut out = String::new();
+    let failed = report
+        .checked
+        .iter()
+        .filter(|p| report.findings.iter().any(|f| &f.file == *p))
+        .count();
+
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(out, r#"<testsuites name="gitvote" tests="{}" failures="{}">"#, report.checked.len(), failed);
+    let _ = writeln!(out, r#"  <testsuite name="gitvote validate" tests="{}" failures="{}">"#, report.checked.len(), failed);
+
+    for path in &report.checked {
+        let name = escape_xml(&path_str(path));
+        let findings: Vec<_> = report.findings.iter().filter(|f| &f.file == path).collect();
+
+        if findings.is_empty() {
+            let _ = writeln!(out, r#"    <testcase name="{0}" classname="gitvote" file="{0}"/>"#, name);
+            continue;
+        }
+
+        let _ = writeln!(out, r#"    <testcase name="{0}" classname="gitvote" file="{0}">"#, name);
+        for f in findings {
+            let _ = writeln!(
+                out,
+                r#"      <failure type="{}" message="{}">{}</failure>"#,
+                f.rule,
+                escape_xml(&f.message),
+                escape_xml(&format!("{}:{}", path_str(&f.file), f.line.unwrap_or(1)))
+            );
+        }
+        let _ = writeln!(out, "    </testcase>");
+    }
+
+    let _ = writeln!(out, "  </testsuite>");
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
+
+fn render_sarif(report: &CheckReport) -> String {
+    let mut rule_ids: Vec<&str> = report.findings.iter().map(|f| f.rule).collect();
+    rule_ids.sort();
+    rule_ids.dedup();
+
+    let rules: Vec<_> = rule_ids.iter().map(|id| json!({ "id": id })).collect();
+    let results: Vec<_> = report
+        .findings
+        .iter()
+        .map(|f| {
+            json!({
+                "ruleId": f.rule,
+                "level": "error",
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": path_str(&f.file) },
+                        "region": { "startLine": f.line.unwrap_or(1) },
+                    }
+                }],
+            })
+        })
+        .collect();
+
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "gitvote",
+                    "informationUri": "https://github.com/ducks/gitvote",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&sarif).expect("json value serializes")
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn escape_github_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_github_property(s: &str) -> String {
+    escape_github_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
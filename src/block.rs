@@ -1,13 +1,61 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use crate::delegation::DelegationScope;
+use crate::vote::Ballot;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Block {
     pub index: usize,
     pub timestamp: DateTime<Utc>,
-    pub choice: String,
+    #[serde(flatten)]
+    pub ballot: Ballot,
     pub voter: String,
     pub prev_hash: Option<String>,
     pub hash: String,
     pub signature: String,
+
+    /// Weight the voter's ballot counts with, from the schema's weight
+    /// ledger; absent in unweighted votes, where every ballot counts once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u64>,
+
+    /// Under `first_wins`, index of the voter's earlier vote, which is
+    /// counted instead of this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<usize>,
+
+    /// Under `last_wins`, index of the voter's earlier vote this one
+    /// replaces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<usize>,
+
+    /// Marks a withdrawal record: index of the vote block it withdraws.
+    /// The record repeats that vote's ballot, with the voter, signature and
+    /// timestamp of the withdrawal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdraws: Option<usize>,
+
+    /// Marks a delegation record: the scope of `voter`'s delegation to the
+    /// voter named in its `choice`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegation: Option<DelegationScope>,
+
+    /// When `build-chain` first recorded this block; rebuilding keeps it.
+    /// The voting window is checked against this, never against the
+    /// self-reported `timestamp` or the voter's commit date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<DateTime<Utc>>,
+}
+
+impl Block {
+    /// Weight this block's ballot is counted with.
+    pub fn weight(&self) -> u64 {
+        self.weight.unwrap_or(1)
+    }
+
+    /// When this block's vote was cast, for the voting window: when it was
+    /// recorded, or now for a block from before blocks were stamped.
+    pub fn cast_at(&self) -> DateTime<Utc> {
+        self.recorded_at.unwrap_or_else(Utc::now)
+    }
 }
@@ -1,46 +1,316 @@
+use std::collections::HashMap;
 use std::fs;
-use chrono::Utc;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
 use sha2::{Sha256, Digest};
-use std::error::Error;
+use crate::annotate::{self, CheckReport, Finding, Scope};
 use crate::block::Block;
-use crate::vote::Vote;
+use crate::delegation::{self, Delegation, DELEGATIONS_DIR};
+use crate::error::{GitVoteError, Result};
+use crate::git::files_in_commit_order;
+use crate::schema::{load_schema, Revote, Schema};
+use crate::vote::{vote_paths, Ballot, Vote};
+use crate::withdrawal::{self, Withdrawal, WITHDRAWALS_DIR};
 
-pub fn build() -> Result<(), Box<dyn Error>> {
+pub fn build() -> Result<()> {
+    let count = write()?;
+    println!("✔ Chain built with {} blocks.", count);
+    Ok(())
+}
+
+/// Writes the [`current`] chain to `blocks/`, returning how many blocks it
+/// has.
+pub fn write() -> Result<usize> {
     fs::create_dir_all("blocks")?;
     fs::create_dir_all("votes")?;
 
-    let mut entries: Vec<_> = fs::read_dir("votes")?
-        .filter_map(Result::ok)
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("json"))
+    let schema = load_schema()?;
+    let blocks = current(&schema)?;
+    for block in &blocks {
+        let file = format!("blocks/block-{:04}.json", block.index);
+        fs::write(&file, serde_json::to_string_pretty(block).expect("block serializes"))?;
+    }
+    Ok(blocks.len())
+}
+
+/// Something recorded in the chain, with when the chain first recorded it.
+pub enum Entry {
+    Vote { vote: Vote, recorded_at: Option<DateTime<Utc>> },
+    /// A withdrawal of the vote recorded by the entry at index `of`
+    Withdrawal { withdrawal: Withdrawal, of: usize, recorded_at: Option<DateTime<Utc>> },
+    Delegation { delegation: Delegation, recorded_at: Option<DateTime<Utc>> },
+}
+
+// In the order files committed together are recorded
+enum File {
+    Vote(Vote),
+    Withdrawal(Withdrawal),
+    Delegation(Delegation),
+}
+
+/// The chain `build` would write for the votes, withdrawals and delegations
+/// in the working tree: one block per vote and per valid withdrawal or
+/// delegation, in the order they were committed. Blocks already built keep their `recorded_at`; new
+/// ones are stamped now.
+pub fn current(schema: &Schema) -> Result<Vec<Block>> {
+    let built = built()?;
+    let mut votes = vec![];
+    for path in vote_paths()? {
+        let content = fs::read_to_string(&path)?;
+        let vote: Vote = serde_json::from_str(&content).map_err(|e| {
+            GitVoteError::Validation(format!("Malformed vote file {:?}: {}", path, e))
+        })?;
+        votes.push((path, vote));
+    }
+    let withdrawals = withdrawal::valid_withdrawals(schema, &votes)?;
+    let delegations = delegation::valid_delegations(schema)?;
+
+    // Interleaved by commit; uncommitted files last, votes before the
+    // withdrawals that may name them
+    let committed = files_in_commit_order(&["votes", WITHDRAWALS_DIR, DELEGATIONS_DIR]).unwrap_or_default();
+    let position = |path: &PathBuf| committed.iter().position(|c| c == path).unwrap_or(usize::MAX);
+    let mut files: Vec<(usize, u8, PathBuf, File)> = votes
+        .into_iter()
+        .map(|(path, vote)| (position(&path), 0, path, File::Vote(vote)))
+        .chain(withdrawals.into_iter().map(|(path, w)| (position(&path), 1, path, File::Withdrawal(w))))
+        .chain(delegations.into_iter().map(|(path, d)| (position(&path), 2, path, File::Delegation(d))))
         .collect();
+    files.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+
+    let mut entries = vec![];
+    let mut recorded: HashMap<PathBuf, usize> = HashMap::new();
+    for (_, _, path, file) in files {
+        match file {
+            File::Vote(vote) => {
+                let recorded_at = Some(recorded_at(&built, &vote.voter, &vote.signature, vote.timestamp));
+                recorded.insert(path, entries.len());
+                entries.push(Entry::Vote { vote, recorded_at });
+            }
+            // A withdrawal of a vote not recorded before it has nothing to
+            // withdraw
+            File::Withdrawal(withdrawal) => {
+                if let Some(of) = recorded.remove(&withdrawal.vote) {
+                    let recorded_at =
+                        Some(recorded_at(&built, &withdrawal.voter, &withdrawal.signature, withdrawal.timestamp));
+                    entries.push(Entry::Withdrawal { withdrawal, of, recorded_at });
+                }
+            }
+            File::Delegation(delegation) => {
+                let recorded_at =
+                    Some(recorded_at(&built, &delegation.delegator, &delegation.signature, delegation.timestamp));
+                entries.push(Entry::Delegation { delegation, recorded_at });
+            }
+        }
+    }
+
+    Ok(link_entries(schema, entries))
+}
+
+/// The blocks already in `blocks/`, in order, leaving out any that do not
+/// parse; [`check_chain`] reports those.
+pub fn built() -> Result<Vec<Block>> {
+    let Ok(dir) = fs::read_dir("blocks") else {
+        return Ok(vec![]);
+    };
+    let mut paths: Vec<PathBuf> = dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+
+    let mut blocks = vec![];
+    for path in paths {
+        if let Ok(block) = serde_json::from_str(&fs::read_to_string(&path)?) {
+            blocks.push(block);
+        }
+    }
+    Ok(blocks)
+}
+
+/// When a block in `built` first recorded the vote or withdrawal `voter`
+/// signed with `signature` at `timestamp`, or now if none has. This, not
+/// the timestamp or the commit date, both of which the voter sets, is what
+/// the voting window is checked against.
+pub fn recorded_at(built: &[Block], voter: &str, signature: &str, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+    built
+        .iter()
+        .find(|b| b.voter == voter && b.signature == signature && b.timestamp == timestamp)
+        .and_then(|b| b.recorded_at)
+        .unwrap_or_else(Utc::now)
+}
+
+/// Turns `votes`, in the order they were cast, into hashed blocks, each
+/// linked to the one before and recording the weight the schema gives its
+/// voter and, under a revote policy, which vote it supersedes or is
+/// superseded by.
+pub fn link(schema: &Schema, votes: Vec<Vote>) -> Vec<Block> {
+    link_entries(schema, votes.into_iter().map(|vote| Entry::Vote { vote, recorded_at: None }).collect())
+}
 
-    entries.sort_by_key(|e| e.path().to_path_buf());
+/// Like [`link`], for votes, withdrawals and delegations. Each entry only
+/// adds a block: a revote is recorded on the new vote, and a withdrawal or
+/// delegation in a block of its own, so blocks already built never change. A withdrawn vote leaves its
+/// voter without a vote until they cast a new one.
+pub fn link_entries(schema: &Schema, entries: Vec<Entry>) -> Vec<Block> {
+    // The block of each voter's vote that counts so far
+    let mut counting: HashMap<String, usize> = HashMap::new();
     let mut prev_hash = "GENESIS".to_string();
+    let mut blocks: Vec<Block> = vec![];
 
-    for (index, entry) in entries.iter().enumerate() {
-        let content = fs::read_to_string(entry.path())?;
-        let vote: Vote = serde_json::from_str(&content)?;
-
-        let block = Block {
-            index,
-            voter: vote.voter,
-            choice: vote.choice,
-            signature: vote.signature,
-            prev_hash: Some(prev_hash.clone()),
-            hash: String::new(),
-            timestamp: Utc::now(),
+    for (index, entry) in entries.into_iter().enumerate() {
+        let block = match entry {
+            Entry::Vote { vote, recorded_at } => {
+                let earlier = counting.get(&vote.voter).copied();
+                let (superseded_by, supersedes) = match (schema.revote, earlier) {
+                    (Revote::FirstWins, Some(first)) => (Some(first), None),
+                    (Revote::LastWins, Some(previous)) => (None, Some(previous)),
+                    _ => (None, None),
+                };
+                if superseded_by.is_none() {
+                    counting.insert(vote.voter.clone(), index);
+                }
+                Block {
+                    index,
+                    weight: schema.weight_of(&vote.voter),
+                    superseded_by,
+                    supersedes,
+                    withdraws: None,
+                    delegation: None,
+                    voter: vote.voter,
+                    ballot: vote.ballot,
+                    signature: vote.signature,
+                    prev_hash: Some(prev_hash.clone()),
+                    hash: String::new(),
+                    timestamp: vote.timestamp,
+                    recorded_at,
+                }
+            }
+            Entry::Withdrawal { withdrawal, of, recorded_at } => {
+                if counting.get(&withdrawal.voter) == Some(&of) {
+                    counting.remove(&withdrawal.voter);
+                }
+                Block {
+                    index,
+                    weight: None,
+                    superseded_by: None,
+                    supersedes: None,
+                    withdraws: Some(of),
+                    delegation: None,
+                    voter: withdrawal.voter,
+                    ballot: blocks[of].ballot.clone(),
+                    signature: withdrawal.signature,
+                    prev_hash: Some(prev_hash.clone()),
+                    hash: String::new(),
+                    timestamp: withdrawal.timestamp,
+                    recorded_at,
+                }
+            }
+            Entry::Delegation { delegation, recorded_at } => Block {
+                index,
+                weight: None,
+                superseded_by: None,
+                supersedes: None,
+                withdraws: None,
+                delegation: Some(delegation.scope),
+                voter: delegation.delegator,
+                ballot: Ballot::Choice(delegation.delegate),
+                signature: delegation.signature,
+                prev_hash: Some(prev_hash.clone()),
+                hash: String::new(),
+                timestamp: delegation.timestamp,
+                recorded_at,
+            },
         };
 
-        let raw = serde_json::to_string(&block)?;
-        let hash = format!("{:x}", Sha256::digest(raw.as_bytes()));
+        let hash = block_hash(&block);
+        prev_hash = hash.clone();
+        blocks.push(Block { hash, ..block });
+    }
 
-        let finalized = Block { hash: hash.clone(), ..block };
-        let file = format!("blocks/block-{:04}.json", index);
-        fs::write(&file, serde_json::to_string_pretty(&finalized)?)?;
+    blocks
+}
 
-        prev_hash = hash;
+/// SHA-256 over the block's JSON with an empty `hash` field.
+pub fn block_hash(block: &Block) -> String {
+    let unhashed = Block { hash: String::new(), ..block.clone() };
+    let raw = serde_json::to_string(&unhashed).expect("block serializes");
+    format!("{:x}", Sha256::digest(raw.as_bytes()))
+}
+
+/// Verifies that every block in `blocks/` parses, is numbered in order,
+/// links to its predecessor and carries the hash of its own contents.
+pub fn check_chain() -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+    let blocks_path = Path::new("blocks");
+
+    if !blocks_path.exists() {
+        return Ok(report);
     }
 
-    println!("✔ Chain built with {} blocks.", entries.len());
-    Ok(())
+    let mut paths: Vec<_> = fs::read_dir(blocks_path)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+
+    let mut prev_hash = Some("GENESIS".to_string());
+
+    for (index, path) in paths.into_iter().enumerate() {
+        let content = fs::read_to_string(&path)?;
+        report.checked.push(path.clone());
+
+        let finding = |rule, key: &str, message| Finding {
+            file: path.clone(),
+            line: annotate::line_of(&content, key),
+            rule,
+            scope: Scope::Chain,
+            message,
+        };
+
+        let block: Block = match serde_json::from_str(&content) {
+            Ok(block) => block,
+            Err(e) => {
+                report.findings.push(Finding {
+                    line: Some(e.line()),
+                    ..finding("malformed-block", "", format!("Malformed block: {}", e))
+                });
+                prev_hash = None;
+                continue;
+            }
+        };
+
+        if block.index != index {
+            report.findings.push(finding(
+                "block-index",
+                "index",
+                format!("Block index {} found at position {}", block.index, index),
+            ));
+        }
+
+        // After a malformed block there is nothing sound to link against
+        if prev_hash.is_some() && block.prev_hash != prev_hash {
+            report.findings.push(finding(
+                "broken-link",
+                "prev_hash",
+                match index {
+                    0 => "First block does not link to GENESIS".to_string(),
+                    _ => format!("prev_hash does not match the hash of block {}", index - 1),
+                },
+            ));
+        }
+
+        if block_hash(&block) != block.hash {
+            report.findings.push(finding(
+                "hash-mismatch",
+                "hash",
+                "Block contents do not match its hash (tampered?)".to_string(),
+            ));
+        }
+
+        prev_hash = Some(block.hash);
+    }
+
+    Ok(report)
 }
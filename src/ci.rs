@@ -0,0 +1,118 @@
+use std::env;
+use clap::ValueEnum;
+use crate::annotate::OutputFormat;
+use crate::chain;
+use crate::error::{GitVoteError, Result};
+use crate::git::run_git;
+use crate::validate::validate_votes;
+
+/// What triggered the CI run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CiEvent {
+    /// A vote pull/merge request: validate only
+    Pr,
+    /// A push to the proposal branch: validate, rebuild and commit the chain
+    Push,
+}
+
+/// Settings for `gitvote ci`.
+#[derive(Debug, Clone)]
+pub struct CiOptions {
+    /// Overrides detection from the environment.
+    pub event: Option<CiEvent>,
+    pub format: OutputFormat,
+    pub bot_name: String,
+    pub bot_email: String,
+    /// Push the chain commit back to `origin`.
+    pub push: bool,
+    /// Branch to push to; detected from the environment or HEAD if unset.
+    pub branch: Option<String>,
+}
+
+/// Works out the event from well-known CI variables: `GITVOTE_EVENT`
+/// (`pr`/`push`), then GitHub Actions, then GitLab CI.
+pub fn detect_event(var: impl Fn(&str) -> Option<String>) -> Option<CiEvent> {
+    if let Some(event) = var("GITVOTE_EVENT") {
+        return CiEvent::from_str(&event, true).ok();
+    }
+
+    if let Some(event) = var("GITHUB_EVENT_NAME") {
+        return match event.as_str() {
+            "pull_request" | "pull_request_target" => Some(CiEvent::Pr),
+            "push" => Some(CiEvent::Push),
+            _ => None,
+        };
+    }
+
+    if var("CI_MERGE_REQUEST_IID").is_some() {
+        return Some(CiEvent::Pr);
+    }
+    if var("CI_PIPELINE_SOURCE").as_deref() == Some("push") {
+        return Some(CiEvent::Push);
+    }
+
+    None
+}
+
+pub fn run_ci(options: &CiOptions) -> Result<()> {
+    let event = options
+        .event
+        .or_else(|| detect_event(|name| env::var(name).ok()))
+        .ok_or_else(|| {
+            GitVoteError::Usage(
+                "Could not tell whether this is a PR or a push; pass --event or set GITVOTE_EVENT.".into(),
+            )
+        })?;
+
+    // Progress goes to stderr: stdout carries the findings in the
+    // requested format
+    eprintln!("▶ gitvote ci ({:?})", event);
+    validate_votes(options.format, None)?;
+
+    if event == CiEvent::Pr {
+        return Ok(());
+    }
+
+    let count = chain::write()?;
+    eprintln!("✔ Chain built with {} blocks.", count);
+
+    if run_git(&["status", "--porcelain", "--", "blocks"])?.is_empty() {
+        eprintln!("✔ Chain already up to date.");
+        return Ok(());
+    }
+
+    let name = format!("user.name={}", options.bot_name);
+    let email = format!("user.email={}", options.bot_email);
+    run_git(&["add", "blocks"])?;
+    run_git(&["-c", &name, "-c", &email, "commit", "-m", "Update chain after merge"])?;
+    eprintln!("✔ Committed updated chain as {} <{}>", options.bot_name, options.bot_email);
+
+    if options.push {
+        let branch = match &options.branch {
+            Some(branch) => branch.clone(),
+            None => current_branch()?,
+        };
+        run_git(&["push", "origin", &format!("HEAD:refs/heads/{}", branch)])?;
+        eprintln!("✔ Pushed chain to origin/{}", branch);
+    }
+
+    Ok(())
+}
+
+/// Branch being built; CI checkouts are often detached, so prefer the
+/// provider's variables over HEAD.
+fn current_branch() -> Result<String> {
+    for var in ["GITHUB_REF_NAME", "CI_COMMIT_BRANCH"] {
+        if let Ok(branch) = env::var(var)
+            && !branch.is_empty()
+        {
+            return Ok(branch);
+        }
+    }
+
+    let branch = run_git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+    if branch == "HEAD" {
+        return Err(GitVoteError::Usage("Detached HEAD; pass --branch to choose where to push.".into()));
+    }
+    Ok(branch)
+}
@@ -0,0 +1,351 @@
+//! Liquid democracy: voters may hand their weight to another voter instead
+//! of voting themselves.
+//!
+//! - A delegation is a signed file in `delegations/`, like a vote, and is
+//!   recorded in the chain as a block of its own. One scoped to the proposal
+//!   overrides a global one (committed on the default branch and inherited
+//!   by every proposal branch); among several of the same scope the one
+//!   committed last counts, so a voter changes their delegate by delegating
+//!   again.
+//! - One scoped to the proposal must be recorded inside the voting window; a
+//!   global one only before it closes, since it may well predate the
+//!   proposal.
+//! - Delegations are followed transitively until they reach someone who
+//!   voted. Voting directly always overrides one's own delegation.
+//! - A chain that loops back on itself before reaching a voter is a cycle
+//!   and its weight is not counted; so is one ending at someone who did not
+//!   vote.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::annotate::{self, CheckReport, Finding, Scope};
+use crate::block::Block;
+use crate::error::Result;
+use crate::git::json_files_in_commit_order;
+use crate::chain;
+use crate::schema::Schema;
+use crate::utils::generate_fake_signature;
+use crate::verdict::direct_support;
+use crate::vote::Ballot;
+
+pub const DELEGATIONS_DIR: &str = "delegations";
+
+/// Which votes a delegation covers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationScope {
+    /// Only the proposal on whose branch the file is committed
+    #[default]
+    Proposal,
+
+    /// Every proposal, until replaced
+    Global,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Delegation {
+    /// Who hands over their vote
+    pub delegator: String,
+
+    /// Who votes on their behalf
+    pub delegate: String,
+
+    #[serde(default)]
+    pub scope: DelegationScope,
+
+    pub signature: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Delegation {
+    /// Text the signature covers.
+    pub fn canonical(&self) -> String {
+        let scope = match self.scope {
+            DelegationScope::Proposal => "proposal",
+            DelegationScope::Global => "global",
+        };
+        format!("delegate:{}:{}", self.delegate, scope)
+    }
+}
+
+/// Where following a delegator's delegations ends up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum End {
+    /// At a voter who cast a ballot, who receives the weight
+    Voted(String),
+    /// Back at a delegate already passed through
+    Cycle,
+    /// At someone who neither voted nor delegated
+    NoVote,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub delegator: String,
+    /// Delegates followed, in order.
+    pub path: Vec<String>,
+    pub end: End,
+}
+
+/// Delegation files in `delegations/`, in the order they were committed.
+pub fn delegation_paths() -> Result<Vec<PathBuf>> {
+    json_files_in_commit_order(DELEGATIONS_DIR)
+}
+
+/// Why `delegation`, made at `made`, falls outside the voting window, if it
+/// does. `made` is when the chain recorded it (see [`chain::recorded_at`]).
+pub fn window_problem(schema: &Schema, delegation: &Delegation, made: DateTime<Utc>) -> Option<String> {
+    let open = match delegation.scope {
+        DelegationScope::Proposal => schema.is_open_at(made),
+        DelegationScope::Global => schema.closes_at.is_none_or(|closes| made <= closes),
+    };
+    (!open).then(|| format!("Delegated at {} outside the voting window", made.to_rfc3339()))
+}
+
+/// Delegation files the chain records, with their delegation, in the
+/// order they were committed: well formed, signed by the delegator, not to
+/// themselves and inside the voting window. [`check_delegations`] reports
+/// the rest.
+pub fn valid_delegations(schema: &Schema) -> Result<Vec<(PathBuf, Delegation)>> {
+    let built = chain::built()?;
+    let mut valid = vec![];
+    for path in delegation_paths()? {
+        let content = fs::read_to_string(&path)?;
+        let Ok(delegation) = serde_json::from_str::<Delegation>(&content) else {
+            continue;
+        };
+        let made = chain::recorded_at(&built, &delegation.delegator, &delegation.signature, delegation.timestamp);
+        if delegation.delegator != delegation.delegate
+            && delegation.signature == generate_fake_signature(&delegation.delegator, &delegation.canonical())
+            && window_problem(schema, &delegation, made).is_none()
+        {
+            valid.push((path, delegation));
+        }
+    }
+    Ok(valid)
+}
+
+/// The delegation a chain block records, if it is a delegation record.
+pub fn recorded(block: &Block) -> Option<Delegation> {
+    let Ballot::Choice(delegate) = &block.ballot else {
+        return None;
+    };
+    Some(Delegation {
+        delegator: block.voter.clone(),
+        delegate: delegate.clone(),
+        scope: block.delegation?,
+        signature: block.signature.clone(),
+        timestamp: block.timestamp,
+    })
+}
+
+/// The delegation in force for each delegator, from `delegations` in
+/// commit order: the last one scoped to the proposal, else the last global
+/// one.
+pub fn effective(delegations: &[Delegation]) -> BTreeMap<String, String> {
+    // Stable, so the last committed of each scope still comes last
+    let mut ordered: Vec<&Delegation> = delegations.iter().collect();
+    ordered.sort_by_key(|d| std::cmp::Reverse(d.scope));
+
+    ordered
+        .into_iter()
+        .map(|d| (d.delegator.clone(), d.delegate.clone()))
+        .collect()
+}
+
+/// Follows the delegations of everyone who did not vote themselves.
+pub fn resolve(delegates: &BTreeMap<String, String>, voted: &HashSet<&str>) -> Vec<Resolution> {
+    delegates
+        .keys()
+        .filter(|delegator| !voted.contains(delegator.as_str()))
+        .map(|delegator| {
+            let mut path: Vec<String> = vec![];
+            let mut current = delegator;
+            let end = loop {
+                let Some(next) = delegates.get(current) else {
+                    break End::NoVote;
+                };
+                if next == delegator || path.contains(next) {
+                    path.push(next.clone());
+                    break End::Cycle;
+                }
+                path.push(next.clone());
+                if voted.contains(next.as_str()) {
+                    break End::Voted(next.clone());
+                }
+                current = next;
+            };
+            Resolution { delegator: delegator.clone(), path, end }
+        })
+        .collect()
+}
+
+/// How delegations changed the weight behind each ballot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelegationReport {
+    pub resolutions: Vec<Resolution>,
+    /// Per option, weight from ballots cast directly and weight delegated
+    /// to them; per voter for ballot kinds without direct support.
+    pub weights: Vec<(String, u64, u64)>,
+}
+
+/// Adds the weight of every resolved, eligible delegator to the ballot of
+/// the voter their delegations end at. Returns the blocks to count and a report, or
+/// `None` if nobody delegated.
+pub fn apply(schema: &Schema, blocks: &[Block], delegations: &[Delegation]) -> Option<(Vec<Block>, DelegationReport)> {
+    if delegations.is_empty() {
+        return None;
+    }
+
+    // Someone who may not vote cannot hand a vote on either
+    let eligible: Vec<Delegation> =
+        delegations.iter().filter(|d| schema.ineligibility(&d.delegator).is_none()).cloned().collect();
+    let voted: HashSet<&str> = blocks.iter().map(|b| b.voter.as_str()).collect();
+    let resolutions = resolve(&effective(&eligible), &voted);
+
+    let mut delegated: HashMap<&str, u64> = HashMap::new();
+    for r in &resolutions {
+        if let End::Voted(voter) = &r.end {
+            *delegated.entry(voter.as_str()).or_default() += schema.weight_of(&r.delegator).unwrap_or(1);
+        }
+    }
+
+    let received: Vec<Block> = blocks
+        .iter()
+        .map(|b| Block { weight: Some(delegated.get(b.voter.as_str()).copied().unwrap_or(0)), ..b.clone() })
+        .collect();
+
+    // Answers to several questions have no single option to credit
+    let per_option: Option<Vec<(String, u64, u64)>> = match schema.questions {
+        Some(_) => None,
+        None => schema
+            .candidates()
+            .into_iter()
+            .map(|option| Some((option.clone(), direct_support(blocks, &option)?, direct_support(&received, &option)?)))
+            .collect(),
+    };
+    let weights = per_option.unwrap_or_else(|| {
+        blocks
+            .iter()
+            .zip(&received)
+            .map(|(own, extra)| (own.voter.clone(), own.weight(), extra.weight()))
+            .collect()
+    });
+
+    let counted = blocks
+        .iter()
+        .zip(&received)
+        .map(|(own, extra)| match extra.weight() {
+            0 => own.clone(),
+            w => Block { weight: Some(own.weight() + w), ..own.clone() },
+        })
+        .collect();
+
+    Some((counted, DelegationReport { resolutions, weights }))
+}
+
+/// Checks every delegation file: signature, no self-delegation, the voting
+/// window, an eligible delegator for those scoped to the proposal, and no
+/// cycle left after `voters` voted directly.
+pub fn check_delegations(schema: &Schema, voters: &[String]) -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+    let built = chain::built()?;
+    let mut delegations = vec![];
+    let mut files = vec![];
+
+    for path in delegation_paths()? {
+        let content = fs::read_to_string(&path)?;
+        report.checked.push(path.clone());
+
+        let finding = |rule, key: &str, message| Finding {
+            file: path.clone(),
+            line: annotate::line_of(&content, key),
+            rule,
+            scope: Scope::Vote,
+            message,
+        };
+
+        let delegation: Delegation = match serde_json::from_str(&content) {
+            Ok(delegation) => delegation,
+            Err(e) => {
+                report.findings.push(Finding {
+                    line: Some(e.line()),
+                    ..finding("malformed-delegation", "", format!("Malformed delegation file: {}", e))
+                });
+                continue;
+            }
+        };
+
+        if delegation.delegator == delegation.delegate {
+            report.findings.push(finding(
+                "self-delegation",
+                "delegate",
+                format!("{} delegates to themselves", delegation.delegator),
+            ));
+        }
+
+        if delegation.signature != generate_fake_signature(&delegation.delegator, &delegation.canonical()) {
+            report.findings.push(finding(
+                "bad-signature",
+                "signature",
+                format!("Signature mismatch for delegator {}", delegation.delegator),
+            ));
+        }
+
+        let made = chain::recorded_at(&built, &delegation.delegator, &delegation.signature, delegation.timestamp);
+        if let Some(message) = window_problem(schema, &delegation, made) {
+            report.findings.push(finding("outside-window", "timestamp", message));
+        }
+
+        if delegation.scope == DelegationScope::Proposal
+            && let Some(message) = schema.ineligibility(&delegation.delegator)
+        {
+            report.findings.push(finding("ineligible-voter", "delegator", message));
+        }
+
+        files.push((path, content, delegation.delegator.clone()));
+        delegations.push(delegation);
+    }
+
+    let voted: HashSet<&str> = voters.iter().map(String::as_str).collect();
+    for r in resolve(&effective(&delegations), &voted) {
+        if r.end != End::Cycle {
+            continue;
+        }
+        if let Some((path, content, _)) = files.iter().rfind(|(_, _, d)| *d == r.delegator) {
+            report.findings.push(Finding {
+                file: path.clone(),
+                line: annotate::line_of(content, "delegate"),
+                rule: "delegation-cycle",
+                scope: Scope::Vote,
+                message: format!("Delegation cycle: {} → {}", r.delegator, r.path.join(" → ")),
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+impl fmt::Display for DelegationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Delegations:")?;
+        for r in &self.resolutions {
+            let chain = format!("{} → {}", r.delegator, r.path.join(" → "));
+            match &r.end {
+                End::Voted(_) => writeln!(f, "  {}", chain)?,
+                End::Cycle => writeln!(f, "  {} (cycle, not counted)", chain)?,
+                End::NoVote => writeln!(f, "  {} (no vote cast, not counted)", chain)?,
+            }
+        }
+
+        writeln!(f, "Direct + delegated weight:")?;
+        for (name, direct, delegated) in &self.weights {
+            writeln!(f, "  {}: {} + {}", name, direct, delegated)?;
+        }
+        Ok(())
+    }
+}
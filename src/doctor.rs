@@ -1,50 +1,38 @@
-use std::error::Error;
-use std::process::Command;
 use std::path::Path;
+use crate::error::{GitVoteError, Result};
+use crate::git::run_git;
 
-pub fn run_doctor_check() -> Result<(), Box<dyn Error>> {
+pub fn run_doctor_check() -> Result<()> {
     println!("🩺 GitVote Doctor Check");
     println!("------------------------");
 
     // Check for Git repo
     if !Path::new(".git").exists() {
-        return Err("Not in a Git repository.".into());
+        return Err(GitVoteError::Git("Not in a Git repository.".into()));
     }
     println!("✔ Git repository detected.");
 
     // Check for GPG signing key configured
-    let signing_key = Command::new("git")
-        .args(["config", "--get", "user.signingkey"])
-        .output()?;
-
-    let signing_key = String::from_utf8(signing_key.stdout)?.trim().to_string();
+    let signing_key = run_git(&["config", "--default", "", "user.signingkey"])?;
 
     if signing_key.is_empty() {
-        return Err("❌ No GPG signing key configured.".into());
+        return Err(GitVoteError::Git("❌ No GPG signing key configured.".into()));
     }
 
     println!("✔ GPG signing key configured: {}", signing_key);
 
     // Check if commit signing is enabled
-    let gpgsign = Command::new("git")
-        .args(["config", "--get", "commit.gpgsign"])
-        .output()?;
-
-    let gpgsign = String::from_utf8(gpgsign.stdout)?.trim().to_string();
+    let gpgsign = run_git(&["config", "--default", "", "commit.gpgsign"])?;
 
     if gpgsign != "true" {
-        return Err("❌ Git commit signing is not enabled (commit.gpgsign != true).".into());
+        return Err(GitVoteError::Git("❌ Git commit signing is not enabled (commit.gpgsign != true).".into()));
     }
 
     println!("✔ Git commit signing enabled.");
 
     // Optional: try signing a dry-run commit to verify full signing works
-    let dry_run = Command::new("git")
-        .args(["commit", "--allow-empty", "--dry-run", "-S", "-m", "test"])
-        .output()?;
-
-    if !dry_run.status.success() {
-        return Err("❌ GPG signing failed during dry-run commit.".into());
+    if run_git(&["commit", "--allow-empty", "--dry-run", "-S", "-m", "test"]).is_err() {
+        return Err(GitVoteError::Git("❌ GPG signing failed during dry-run commit.".into()));
     }
 
     println!("✔ Dry-run commit signing successful.");
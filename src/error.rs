@@ -0,0 +1,70 @@
+use std::fmt;
+use std::io;
+
+/// Every failure GitVote can report, grouped by what went wrong so that
+/// wrappers (CI scripts, bots) can tell them apart via the exit code.
+#[derive(Debug)]
+pub enum GitVoteError {
+    /// The command was invoked wrongly or could not work out what to do.
+    Usage(String),
+
+    /// `git` is not installed, failed to run, or we are not inside a repo.
+    Git(String),
+
+    /// `schema.json` is missing or malformed.
+    Schema(String),
+
+    /// A vote file was rejected (bad choice, duplicate voter, bad signature).
+    Validation(String),
+
+    /// Blocks are missing, malformed or do not link up.
+    Chain(String),
+
+    /// Any other filesystem failure.
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, GitVoteError>;
+
+impl GitVoteError {
+    /// Process exit code for this category. Documented in the README; keep
+    /// these stable since CI wrappers match on them.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            GitVoteError::Usage(_) => 2,
+            GitVoteError::Git(_) => 3,
+            GitVoteError::Schema(_) => 4,
+            GitVoteError::Validation(_) => 5,
+            GitVoteError::Chain(_) => 6,
+            GitVoteError::Io(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for GitVoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitVoteError::Usage(msg) => write!(f, "{}", msg),
+            GitVoteError::Git(msg) => write!(f, "git error: {}", msg),
+            GitVoteError::Schema(msg) => write!(f, "schema error: {}", msg),
+            GitVoteError::Validation(msg) => write!(f, "invalid vote: {}", msg),
+            GitVoteError::Chain(msg) => write!(f, "chain error: {}", msg),
+            GitVoteError::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for GitVoteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GitVoteError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GitVoteError {
+    fn from(err: io::Error) -> Self {
+        GitVoteError::Io(err)
+    }
+}
@@ -1,18 +1,135 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::error::Error;
+use crate::error::{GitVoteError, Result};
 
-pub fn get_git_voter() -> Result<String, Box<dyn Error>> {
-    let name = String::from_utf8(
-        Command::new("git").args(["config", "user.name"]).output()?.stdout
-    )?.trim().to_string();
+/// Runs `git` with the given arguments and returns its trimmed stdout.
+/// Fails if git is missing or exits non-zero.
+pub fn run_git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).output().map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            GitVoteError::Git("git is not installed or not on PATH.".into())
+        } else {
+            GitVoteError::Git(format!("failed to run git: {}", e))
+        }
+    })?;
 
-    let email = String::from_utf8(
-        Command::new("git").args(["config", "user.email"]).output()?.stdout
-    )?.trim().to_string();
+    if !output.status.success() {
+        return Err(GitVoteError::Git(format!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8(output.stdout)
+        .map(|s| s.trim().to_string())
+        .map_err(|_| GitVoteError::Git(format!("`git {}` produced non-UTF-8 output", args.join(" "))))
+}
+
+pub fn get_git_voter() -> Result<String> {
+    let name = run_git(&["config", "--default", "", "user.name"])?;
+    let email = run_git(&["config", "--default", "", "user.email"])?;
 
     if name.is_empty() || email.is_empty() {
-        return Err("Git user.name or user.email not configured.".into());
+        return Err(GitVoteError::Git("Git user.name or user.email not configured.".into()));
     }
 
     Ok(format!("{} <{}>", name, email))
 }
+
+/// Returns the fingerprint of the GPG key that signed `commit_sha`.
+pub fn extract_gpg_fingerprint(commit_sha: &str) -> Result<String> {
+    let stdout = run_git(&["log", "--show-signature", "-1", commit_sha])?;
+
+    for line in stdout.lines() {
+        if let Some(fingerprint) = line.strip_prefix("gpg:                using ") {
+            let parts: Vec<_> = fingerprint.split_whitespace().collect();
+
+            if parts.len() >= 3 && parts[0] == "RSA" && parts[1] == "key" {
+                return Ok(parts[2].to_string());
+            }
+        }
+    }
+
+    Err(GitVoteError::Git(format!("No GPG key found on commit {commit_sha}")))
+}
+
+/// GPG status of a commit, as reported by `git log --format=%G?`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitSignature {
+    /// Good signature from the key with this fingerprint.
+    Good(String),
+    /// Signed, but the key is missing, expired, revoked or untrusted.
+    Unverified(String),
+    /// Signature does not match the commit.
+    Bad,
+    /// Commit is not signed.
+    Unsigned,
+}
+
+pub fn commit_signature(commit_sha: &str) -> Result<CommitSignature> {
+    let out = run_git(&["log", "-1", "--format=%G?%n%GF", commit_sha])?;
+    let mut lines = out.lines();
+    let status = lines.next().unwrap_or("N");
+    let fingerprint = lines.next().unwrap_or("").to_string();
+
+    Ok(match status {
+        "G" => CommitSignature::Good(fingerprint),
+        "B" => CommitSignature::Bad,
+        "N" => CommitSignature::Unsigned,
+        "U" => CommitSignature::Unverified("untrusted key".into()),
+        "X" => CommitSignature::Unverified("expired signature".into()),
+        "Y" => CommitSignature::Unverified("expired key".into()),
+        "R" => CommitSignature::Unverified("revoked key".into()),
+        _ => CommitSignature::Unverified("public key not available".into()),
+    })
+}
+
+/// The commit that added `path`, or `None` if it is not committed yet.
+pub fn commit_adding(path: &Path) -> Result<Option<String>> {
+    let path = path.to_string_lossy();
+    let sha = run_git(&["log", "--diff-filter=A", "--format=%H", "-1", "--", &path])?;
+    Ok(if sha.is_empty() { None } else { Some(sha) })
+}
+
+/// Files under any of `dirs` in the order they reached the current branch,
+/// oldest first. Following only first parents orders files merged from
+/// other branches by their merge, not by commit dates, which voters set.
+pub fn files_in_commit_order(dirs: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut args = vec!["log", "--first-parent", "--reverse", "--diff-filter=A", "--format=", "--name-only", "--"];
+    args.extend(dirs);
+    let out = run_git(&args)?;
+    Ok(out.lines().filter(|l| !l.is_empty()).map(PathBuf::from).collect())
+}
+
+/// JSON files in `dir` in the order they were committed, then any not yet
+/// committed (or outside a git repo) by file name. Empty if `dir` is
+/// missing.
+pub fn json_files_in_commit_order(dir: &str) -> Result<Vec<PathBuf>> {
+    let path = Path::new(dir);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut paths: Vec<_> = fs::read_dir(path)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+
+    // Outside a repo, or before the first commit, there is no history to go by
+    let committed = files_in_commit_order(&[dir]).unwrap_or_default();
+    paths.sort_by_key(|p| committed.iter().position(|c| c == p).unwrap_or(usize::MAX));
+
+    Ok(paths)
+}
+
+/// Files under `dir` added between `base` and `HEAD`.
+pub fn files_added_since(base: &str, dir: &str) -> Result<Vec<PathBuf>> {
+    let range = format!("{}...HEAD", base);
+    let out = run_git(&["diff", "--name-only", "--diff-filter=A", &range, "--", dir])?;
+    Ok(out.lines().map(PathBuf::from).collect())
+}
@@ -0,0 +1,241 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+use crate::error::{GitVoteError, Result};
+use crate::git::run_git;
+
+const ZERO_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// Marker written into installed hooks so we only ever overwrite our own.
+const HOOK_MARKER: &str = "# installed by gitvote";
+
+/// Refs the server-side hook guards; everything else is pushed through.
+const PROPOSAL_PREFIX: &str = "refs/heads/proposal/";
+
+/// Server-side `pre-receive` entry point. Reads `<old> <new> <ref>` lines
+/// and validates every commit pushed to a `proposal/*` branch whose tree has
+/// a `schema.json`. Any problem rejects the whole push.
+pub fn pre_receive(input: impl BufRead) -> Result<()> {
+    let git_dir = PathBuf::from(run_git(&["rev-parse", "--absolute-git-dir"])?);
+    let mut rejected = 0;
+
+    for line in input.lines() {
+        let line = line?;
+        let parts: Vec<_> = line.split_whitespace().collect();
+        let [old, new, refname] = parts[..] else {
+            return Err(GitVoteError::Usage(format!("Unexpected pre-receive input: {}", line)));
+        };
+
+        if !refname.starts_with(PROPOSAL_PREFIX) || new == ZERO_SHA {
+            continue;
+        }
+
+        for sha in pushed_commits(old, new)? {
+            let tree = checkout_commit(&git_dir, &sha)?;
+            if !tree.path().join("schema.json").exists() {
+                continue;
+            }
+
+            let findings = check_tree(tree.path())?;
+            print_findings(&format!("{} {}: ", refname, &sha[..7]), &findings);
+            rejected += findings.len();
+        }
+    }
+
+    if rejected > 0 {
+        return Err(GitVoteError::Validation(format!("push rejected, {} problem(s) found", rejected)));
+    }
+    Ok(())
+}
+
+/// Commits introduced by a ref update, oldest first.
+fn pushed_commits(old: &str, new: &str) -> Result<Vec<String>> {
+    let range = format!("{}..{}", old, new);
+    let args: Vec<&str> = if old == ZERO_SHA {
+        // New branch: everything not already reachable from an existing ref
+        vec!["rev-list", "--reverse", new, "--not", "--all"]
+    } else {
+        vec!["rev-list", "--reverse", &range]
+    };
+
+    Ok(run_git(&args)?.lines().map(str::to_string).collect())
+}
+
+/// Writes the tree of `sha` into a new scratch directory, using a private
+/// index so neither the repo's index nor its work tree is touched.
+fn checkout_commit(git_dir: &Path, sha: &str) -> Result<TempDir> {
+    let tree = TempDir::new()?;
+    let index = TempDir::new()?;
+    let index_file = index.path().join("index");
+
+    for args in [vec!["read-tree", sha], vec!["checkout-index", "--all"]] {
+        let status = Command::new("git")
+            .arg(format!("--git-dir={}", git_dir.display()))
+            .arg(format!("--work-tree={}", tree.path().display()))
+            .args(&args)
+            .env("GIT_INDEX_FILE", &index_file)
+            .status()?;
+
+        if !status.success() {
+            return Err(GitVoteError::Git(format!("could not check out {} for validation", sha)));
+        }
+    }
+
+    Ok(tree)
+}
+
+/// Runs `gitvote validate` in `tree` and returns the problems it reports,
+/// one `file[:line]: message` per entry. Validating in a child process
+/// leaves our own working directory alone.
+fn check_tree(tree: &Path) -> Result<Vec<String>> {
+    let output = Command::new(env::current_exe()?).arg("validate").current_dir(tree).output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    match output.status.code() {
+        // Clean, or problems found in votes or blocks
+        Some(0 | 5 | 6) => Ok(stderr.lines().filter_map(|l| l.strip_prefix("✘ ")).map(str::to_string).collect()),
+        code => Err(child_error(code, &stderr)),
+    }
+}
+
+/// Rebuilds the error a failed `gitvote validate` reported, keeping its
+/// category so the hook exits the way `validate` would have.
+fn child_error(code: Option<i32>, stderr: &str) -> GitVoteError {
+    let message = stderr.lines().find_map(|l| l.strip_prefix("Error: ")).unwrap_or("validate failed");
+    let detail = |prefix: &str| message.strip_prefix(prefix).unwrap_or(message).to_string();
+
+    match code {
+        Some(2) => GitVoteError::Usage(message.to_string()),
+        Some(3) => GitVoteError::Git(detail("git error: ")),
+        Some(4) => GitVoteError::Schema(detail("schema error: ")),
+        _ => GitVoteError::Io(io::Error::other(detail("io error: "))),
+    }
+}
+
+fn print_findings(prefix: &str, findings: &[String]) {
+    for finding in findings {
+        eprintln!("✘ {}{}", prefix, finding);
+    }
+}
+
+/// Client-side `pre-commit` entry point: validates what is staged.
+/// Trees without a `schema.json` are not proposal branches and pass.
+pub fn pre_commit() -> Result<()> {
+    let tree = TempDir::new()?;
+    let prefix = format!("{}/", tree.path().display());
+    run_git(&["checkout-index", "--all", &format!("--prefix={}", prefix)])?;
+
+    if !tree.path().join("schema.json").exists() {
+        return Ok(());
+    }
+
+    let findings = check_tree(tree.path())?;
+    print_findings("", &findings);
+
+    if !findings.is_empty() {
+        return Err(GitVoteError::Validation(format!(
+            "commit blocked, {} problem(s) found",
+            findings.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Client-side `pre-push` entry point. Reads
+/// `<local ref> <local sha> <remote ref> <remote sha>` lines and validates
+/// every commit `remote` does not have yet.
+pub fn pre_push(remote: &str, input: impl BufRead) -> Result<()> {
+    let git_dir = PathBuf::from(run_git(&["rev-parse", "--absolute-git-dir"])?);
+    let remotes = format!("--remotes={}", remote);
+    let mut rejected = 0;
+
+    for line in input.lines() {
+        let line = line?;
+        let parts: Vec<_> = line.split_whitespace().collect();
+        let [_, local_sha, remote_ref, _] = parts[..] else {
+            return Err(GitVoteError::Usage(format!("Unexpected pre-push input: {}", line)));
+        };
+
+        if local_sha == ZERO_SHA {
+            continue;
+        }
+
+        let commits = run_git(&["rev-list", "--reverse", local_sha, "--not", &remotes])?;
+        for sha in commits.lines() {
+            let tree = checkout_commit(&git_dir, sha)?;
+            if !tree.path().join("schema.json").exists() {
+                continue;
+            }
+
+            let findings = check_tree(tree.path())?;
+            print_findings(&format!("{} {}: ", remote_ref, &sha[..7]), &findings);
+            rejected += findings.len();
+        }
+    }
+
+    if rejected > 0 {
+        return Err(GitVoteError::Validation(format!("push blocked, {} problem(s) found", rejected)));
+    }
+    Ok(())
+}
+
+/// Installs the server-side `pre-receive` hook into the bare repo at `repo`.
+pub fn install_server(repo: &Path, binary: Option<&Path>, force: bool) -> Result<()> {
+    let repo_arg = repo.to_string_lossy();
+    if run_git(&["-C", &repo_arg, "rev-parse", "--is-bare-repository"])? != "true" {
+        return Err(GitVoteError::Usage(format!(
+            "{} is not a bare repository; use --client for a voter's clone.",
+            repo.display()
+        )));
+    }
+
+    let hooks_dir = PathBuf::from(run_git(&["-C", &repo_arg, "rev-parse", "--absolute-git-dir"])?).join("hooks");
+    write_hook(&hooks_dir, "pre-receive", "hook pre-receive", binary, force)
+}
+
+/// Installs `pre-commit` and `pre-push` hooks into a voter's clone so bad
+/// votes are caught before they leave the machine.
+pub fn install_client(repo: &Path, binary: Option<&Path>, force: bool) -> Result<()> {
+    let repo_arg = repo.to_string_lossy();
+    if run_git(&["-C", &repo_arg, "rev-parse", "--is-bare-repository"])? == "true" {
+        return Err(GitVoteError::Usage(format!(
+            "{} is a bare repository; drop --client to install the server hook.",
+            repo.display()
+        )));
+    }
+
+    let hooks_dir = PathBuf::from(run_git(&["-C", &repo_arg, "rev-parse", "--absolute-git-dir"])?).join("hooks");
+    write_hook(&hooks_dir, "pre-commit", "hook pre-commit", binary, force)?;
+    write_hook(&hooks_dir, "pre-push", "hook pre-push", binary, force)
+}
+
+/// Writes `hooks_dir/name` as a script that runs `gitvote <args>`.
+fn write_hook(hooks_dir: &Path, name: &str, args: &str, binary: Option<&Path>, force: bool) -> Result<()> {
+    let binary = match binary {
+        Some(path) => path.to_path_buf(),
+        None => env::current_exe()?,
+    };
+
+    let path = hooks_dir.join(name);
+    if path.exists() && !force {
+        let existing = fs::read_to_string(&path).unwrap_or_default();
+        if !existing.contains(HOOK_MARKER) {
+            return Err(GitVoteError::Usage(format!(
+                "{} already exists and was not installed by gitvote; pass --force to replace it.",
+                path.display()
+            )));
+        }
+    }
+
+    fs::create_dir_all(hooks_dir)?;
+    let script = format!("#!/bin/sh\n{}\nexec '{}' {} \"$@\"\n", HOOK_MARKER, binary.display(), args);
+    fs::write(&path, script)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+
+    println!("✔ Installed {}", path.display());
+    Ok(())
+}
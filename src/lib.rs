@@ -1,7 +1,22 @@
+pub mod annotate;
 pub mod block;
+pub mod chain;
+pub mod ci;
+pub mod delegation;
+pub mod doctor;
+pub mod error;
 pub mod git;
+pub mod hook;
+pub mod methods;
+pub mod question;
+pub mod report;
 pub mod schema;
+pub mod tally;
+pub mod tiebreak;
 pub mod utils;
 pub mod validate;
+pub mod verdict;
 pub mod vote;
 pub mod voting;
+pub mod withdrawal;
+pub mod writein;
@@ -1,21 +1,19 @@
-use clap::{Parser, Subcommand};
-use std::error::Error;
-
-mod block;
-mod blocks;
-mod chain;
-mod doctor;
-mod git;
-mod tally;
-mod schema;
-mod utils;
-mod validate;
-mod voting;
-mod vote;
+use clap::{Args, Parser, Subcommand};
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use gitvote::annotate::OutputFormat;
+use gitvote::ci::{CiEvent, CiOptions};
+use gitvote::delegation::DelegationScope;
+use gitvote::error::Result;
+use gitvote::vote::Ballot;
+use gitvote::{chain, ci, doctor, hook, report, tally, validate, voting};
 
 #[derive(Parser)]
 #[command(name = "gitvote")]
 #[command(about = "GitVote - Git-native cryptographic voting system", long_about = None)]
+#[command(after_help = "Exit codes: 0 ok, 2 usage, 3 git, 4 schema, 5 invalid vote, 6 chain, 7 io")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -25,13 +23,60 @@ struct Cli {
 enum Commands {
     /// Cast a vote
     Cast {
-        /// Your vote choice
+        #[command(flatten)]
+        ballot: BallotArgs,
+    },
+
+    /// Record the casting vote that breaks a tie (casting voter only)
+    CastingVote {
+        /// The tied option you pick
         #[arg(long)]
         choice: String,
+
+        /// The question the tie is on, in a proposal with several
+        #[arg(long)]
+        question: Option<String>,
+    },
+
+    /// Let another voter vote on your behalf
+    Delegate {
+        /// Voter ID of your delegate
+        #[arg(long)]
+        to: String,
+
+        /// Delegate for every proposal (commit on the default branch)
+        #[arg(long)]
+        global: bool,
+    },
+
+    /// Withdraw your vote while voting is open
+    Withdraw,
+
+    /// Validate all votes and the chain
+    Validate {
+        /// How to report problems (annotations for CI systems)
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
     },
 
-    /// Validate the entire chain
-    Validate,
+    /// Summarize a vote PR for reviewers
+    Report {
+        /// Render as a Markdown document, e.g. for a bot comment
+        #[arg(long)]
+        markdown: bool,
+
+        /// Only list votes added since this ref (usually the PR base branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
 
     /// Build the chain
     BuildChain,
@@ -41,17 +86,212 @@ enum Commands {
 
     /// Check local GPG and Git environment
     Doctor,
+
+    /// Git hooks that validate votes at push time
+    Hook {
+        #[command(subcommand)]
+        action: HookCommand,
+    },
+
+    /// Run the right checks for a CI job, committing the chain on push
+    Ci {
+        /// PR or push; detected from GITVOTE_EVENT, GitHub or GitLab variables if omitted
+        #[arg(long, value_enum)]
+        event: Option<CiEvent>,
+
+        /// How to report validation problems
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Name used for the chain commit
+        #[arg(long, default_value = "gitvote-bot")]
+        bot_name: String,
+
+        /// Email used for the chain commit
+        #[arg(long, default_value = "bot@example.com")]
+        bot_email: String,
+
+        /// Push the chain commit to origin
+        #[arg(long)]
+        push: bool,
+
+        /// Branch to push to (defaults to the CI branch or HEAD)
+        #[arg(long)]
+        branch: Option<String>,
+    },
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+/// At most one of these, matching the proposal's voting method. With none,
+/// the questions of a multi-question proposal are asked one by one.
+#[derive(Args)]
+#[group(required = false, multiple = false)]
+struct BallotArgs {
+    /// Your vote choice
+    #[arg(long)]
+    choice: Option<String>,
+
+    /// Options in order of preference, e.g. `--rank a,b,c`
+    #[arg(long, value_delimiter = ',')]
+    rank: Option<Vec<String>>,
+
+    /// Every option you approve of, e.g. `--approve a,b`
+    #[arg(long, value_delimiter = ',')]
+    approve: Option<Vec<String>>,
+
+    /// A score for each option, e.g. `--score a=5,b=2`
+    #[arg(long, value_delimiter = ',', value_parser = parse_assignment::<i32>)]
+    score: Option<Vec<(String, i32)>>,
+
+    /// Votes to place on each option from your credits, e.g. `--allocate a=3,b=1`
+    #[arg(long, value_delimiter = ',', value_parser = parse_assignment::<u32>)]
+    allocate: Option<Vec<(String, u32)>>,
+
+    /// A grade for each option, e.g. `--grade a=Good,b=Poor`
+    #[arg(long, value_delimiter = ',', value_parser = parse_assignment::<String>)]
+    grade: Option<Vec<(String, String)>>,
+
+    /// An answer to one question of a multi-question proposal, in that
+    /// question's ballot form, e.g. `--answer q1=yes --answer q2=a,b`
+    #[arg(long, value_parser = parse_assignment::<String>)]
+    answer: Option<Vec<(String, String)>>,
+}
+
+/// Parses `option=value` as used by `--score`, `--allocate`, `--grade` and
+/// `--answer`.
+fn parse_assignment<T: std::str::FromStr>(s: &str) -> std::result::Result<(String, T), String> {
+    let (option, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected option=value, got '{}'", s))?;
+    let value = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid value for '{}'", value, option))?;
+    Ok((option.trim().to_string(), value))
+}
+
+impl BallotArgs {
+    /// The ballot given on the command line, if any. `--answer` is read by
+    /// the caller, since its form depends on the schema.
+    fn into_ballot(self) -> Option<Ballot> {
+        let ballot = if let Some(choice) = self.choice {
+            Ballot::Choice(choice)
+        } else if let Some(ranking) = self.rank {
+            Ballot::Ranking(ranking)
+        } else if let Some(approved) = self.approve {
+            // `--approve ""` approves nothing
+            Ballot::Approval(approved.into_iter().filter(|o| !o.is_empty()).collect())
+        } else if let Some(scores) = self.score {
+            Ballot::Scores(scores.into_iter().collect())
+        } else if let Some(votes) = self.allocate {
+            Ballot::Allocation(votes.into_iter().collect())
+        } else if let Some(grades) = self.grade {
+            Ballot::Grades(grades.into_iter().collect())
+        } else {
+            return None;
+        };
+        Some(ballot)
+    }
+}
+
+#[derive(Subcommand)]
+enum HookCommand {
+    /// Server-side hook: validate pushes to proposal/* (reads stdin)
+    PreReceive,
+
+    /// Client-side hook: validate staged votes before committing
+    PreCommit,
+
+    /// Client-side hook: validate commits before pushing (reads stdin)
+    PrePush {
+        /// Name of the remote being pushed to (passed by git)
+        remote: String,
+
+        /// URL of the remote (passed by git, unused)
+        url: Option<String>,
+    },
+
+    /// Install the pre-receive hook into a bare repository, or with
+    /// --client the pre-commit and pre-push hooks into a voter's clone
+    Install {
+        /// Path to the repository
+        #[arg(long, default_value = ".")]
+        repo: PathBuf,
+
+        /// Install voter-side pre-commit and pre-push hooks
+        #[arg(long)]
+        client: bool,
+
+        /// gitvote binary the hook should run (defaults to this one)
+        #[arg(long)]
+        binary: Option<PathBuf>,
+
+        /// Replace an existing hook not installed by gitvote
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Exit codes (stable, matched on by CI wrappers):
+///
+/// | code | meaning                                      |
+/// |------|----------------------------------------------|
+/// | 0    | success                                      |
+/// | 2    | bad command line, or CI event not detectable |
+/// | 3    | git missing, failed, or not in a repo        |
+/// | 4    | `schema.json` missing or malformed           |
+/// | 5    | a vote was rejected                          |
+/// | 6    | blocks missing, malformed or broken          |
+/// | 7    | other filesystem error                       |
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Cast { choice } => voting::cast_vote(&choice)?,
-        Commands::Validate => validate::validate_votes()?,
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+fn run(command: Commands) -> Result<()> {
+    match command {
+        Commands::Cast { mut ballot } => match ballot.answer.take() {
+            Some(answers) => voting::cast_answers(answers)?,
+            None => match ballot.into_ballot() {
+                Some(ballot) => voting::cast_vote(ballot)?,
+                None => voting::cast_interactive(io::stdin().lock(), io::stdout())?,
+            },
+        },
+        Commands::CastingVote { choice, question } => voting::cast_casting_vote(choice, question)?,
+        Commands::Withdraw => voting::withdraw_vote()?,
+        Commands::Delegate { to, global } => {
+            let scope = if global { DelegationScope::Global } else { DelegationScope::Proposal };
+            voting::delegate_vote(to, scope)?
+        }
+        Commands::Validate { format, output } => {
+            validate::validate_votes(format, output.as_deref())?
+        }
+        Commands::Report { markdown, base, output } => {
+            report::print_report(base.as_deref(), markdown, output.as_deref())?
+        }
         Commands::BuildChain => chain::build()?,
         Commands::Tally => tally::tally_votes()?,
         Commands::Doctor => doctor::run_doctor_check()?,
+        Commands::Hook { action } => match action {
+            HookCommand::PreReceive => hook::pre_receive(io::stdin().lock())?,
+            HookCommand::PreCommit => hook::pre_commit()?,
+            HookCommand::PrePush { remote, .. } => hook::pre_push(&remote, io::stdin().lock())?,
+            HookCommand::Install { repo, client: true, binary, force } => {
+                hook::install_client(&repo, binary.as_deref(), force)?
+            }
+            HookCommand::Install { repo, client: false, binary, force } => {
+                hook::install_server(&repo, binary.as_deref(), force)?
+            }
+        },
+        Commands::Ci { event, format, bot_name, bot_email, push, branch } => {
+            ci::run_ci(&CiOptions { event, format, bot_name, bot_email, push, branch })?
+        }
     }
 
     Ok(())
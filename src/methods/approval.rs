@@ -0,0 +1,48 @@
+//! Approval voting: each voter approves any subset of the options and the
+//! option approved by the most voters wins.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalResult {
+    /// Approvals per option, in schema order.
+    pub counts: Vec<(String, u64)>,
+    /// Total weight of the ballots counted.
+    pub ballots: u64,
+    /// Option(s) with the most approvals; several on a tie.
+    pub winners: Vec<String>,
+}
+
+/// Counts `approvals`, each paired with its voter's weight.
+pub fn count(options: &[String], approvals: &[(&[String], u64)]) -> ApprovalResult {
+    let counts: Vec<(String, u64)> = options
+        .iter()
+        .map(|option| {
+            let n = approvals.iter().filter(|(a, _)| a.contains(option)).map(|(_, w)| w).sum();
+            (option.clone(), n)
+        })
+        .collect();
+
+    let top = counts.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let winners = if top == 0 {
+        vec![]
+    } else {
+        counts.iter().filter(|(_, n)| *n == top).map(|(o, _)| o.clone()).collect()
+    };
+
+    ApprovalResult { counts, ballots: approvals.iter().map(|(_, w)| w).sum(), winners }
+}
+
+impl fmt::Display for ApprovalResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (option, n) in &self.counts {
+            writeln!(f, "{} approvals: {} of {}", option, n, self.ballots)?;
+        }
+
+        match self.winners.as_slice() {
+            [] => writeln!(f, "No winner: nothing was approved"),
+            [winner] => writeln!(f, "Winner: {}", winner),
+            tied => writeln!(f, "Tie between: {}", tied.join(", ")),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Borda count: each ballot awards points by position and the option with
+//! the most points wins. Options a ballot leaves unranked get no points.
+
+use std::fmt;
+use crate::schema::BordaPoints;
+
+/// Point totals closer than this are treated as tied.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionPoints {
+    pub option: String,
+    /// `positions[i]`: weight of ballots ranking this option in place `i + 1`.
+    pub positions: Vec<u64>,
+    pub points: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BordaResult {
+    pub scheme: BordaPoints,
+    /// Per option, in schema order.
+    pub options: Vec<OptionPoints>,
+    /// Total weight of the ballots counted.
+    pub ballots: u64,
+    /// Option(s) with the most points; several on a tie.
+    pub winners: Vec<String>,
+}
+
+/// Points a ballot gives its option in place `position` (0-based) when
+/// there are `n` options.
+pub fn points_for(scheme: BordaPoints, n: usize, position: usize) -> f64 {
+    match scheme {
+        BordaPoints::Standard => n.saturating_sub(position + 1) as f64,
+        BordaPoints::Dowdall => 1.0 / (position + 1) as f64,
+    }
+}
+
+/// Counts `rankings`, each paired with its voter's weight.
+pub fn count(options: &[String], scheme: BordaPoints, rankings: &[(&[String], u64)]) -> BordaResult {
+    let n = options.len();
+
+    // Points are derived from the position counts, so the printed table is
+    // all an auditor needs to recompute them
+    let options: Vec<OptionPoints> = options
+        .iter()
+        .map(|option| {
+            let mut positions = vec![0u64; n];
+            for (ranking, weight) in rankings {
+                if let Some(i) = ranking.iter().position(|r| r == option) {
+                    positions[i] += weight;
+                }
+            }
+            let points = positions
+                .iter()
+                .enumerate()
+                .map(|(i, c)| *c as f64 * points_for(scheme, n, i))
+                .sum();
+            OptionPoints { option: option.clone(), positions, points }
+        })
+        .collect();
+
+    let best = options.iter().map(|o| o.points).fold(0.0, f64::max);
+    let winners = if best == 0.0 {
+        vec![]
+    } else {
+        options
+            .iter()
+            .filter(|o| best - o.points < EPSILON)
+            .map(|o| o.option.clone())
+            .collect()
+    };
+
+    BordaResult { scheme, options, ballots: rankings.iter().map(|(_, w)| w).sum(), winners }
+}
+
+impl fmt::Display for BordaResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = self.options.len();
+        let per_place: Vec<String> = (0..n)
+            .map(|i| format_points(self.scheme, points_for(self.scheme, n, i)))
+            .collect();
+        writeln!(f, "Points per place ({:?}): {}", self.scheme, per_place.join(", "))?;
+
+        for o in &self.options {
+            let places: Vec<String> = o
+                .positions
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("#{}: {}", i + 1, c))
+                .collect();
+            writeln!(
+                f,
+                "{}: {} points ({})",
+                o.option,
+                format_points(self.scheme, o.points),
+                places.join(", ")
+            )?;
+        }
+
+        match self.winners.as_slice() {
+            [] => writeln!(f, "No winner: no points awarded"),
+            [winner] => writeln!(f, "Winner: {}", winner),
+            tied => writeln!(f, "Tie between: {}", tied.join(", ")),
+        }
+    }
+}
+
+fn format_points(scheme: BordaPoints, points: f64) -> String {
+    match scheme {
+        BordaPoints::Standard => format!("{}", points),
+        BordaPoints::Dowdall => format!("{:.3}", points),
+    }
+}
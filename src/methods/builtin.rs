@@ -0,0 +1,309 @@
+//! The voting methods that ship with gitvote, one per `Method` variant.
+
+use std::collections::{BTreeMap, HashSet};
+use crate::block::Block;
+use crate::error::{GitVoteError, Result};
+use crate::schema::{Method, Schema, Unscored};
+use crate::vote::Ballot;
+use crate::writein;
+use super::{approval, borda, credits, irv, judgment, plurality, schulze, score, stv, BallotError, Outcome, VotingMethod};
+
+/// A built-in method, registered under its schema name. Only gitvote
+/// creates these, one per entry of [`Method::BUILTIN`].
+pub struct Builtin(pub(crate) Method);
+
+impl VotingMethod for Builtin {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn check_ballot(&self, schema: &Schema, ballot: &Ballot) -> std::result::Result<(), BallotError> {
+        check(schema, &self.0, ballot)
+    }
+
+    fn tally(&self, schema: &Schema, blocks: &mut dyn Iterator<Item = &Block>) -> Result<Outcome> {
+        let ballots: Vec<(&Ballot, u64)> = blocks.map(|b| (&b.ballot, b.weight())).collect();
+        count(schema, &self.0, &ballots)
+    }
+}
+
+/// Checks that `ballot` is the kind the built-in `method` expects and only
+/// uses allowed options.
+fn check(schema: &Schema, method: &Method, ballot: &Ballot) -> std::result::Result<(), BallotError> {
+    match (method, ballot) {
+        (Method::Plurality, Ballot::Choice(choice)) => {
+            if writein::resolve(schema, choice).is_none() {
+                return Err(BallotError::new(
+                    "invalid-choice",
+                    format!("Invalid choice '{}'. Allowed: {:?}", choice, schema.allowed),
+                ));
+            }
+            Ok(())
+        }
+        (Method::Irv | Method::Schulze | Method::Stv | Method::Borda, Ballot::Ranking(ranking)) => check_ranking(schema, ranking),
+        (Method::Approval, Ballot::Approval(approved)) => check_approval(schema, approved),
+        (Method::Score, Ballot::Scores(scores)) => check_scores(schema, scores),
+        (Method::Cumulative | Method::Quadratic, Ballot::Allocation(votes)) => check_allocation(schema, votes),
+        (Method::MajorityJudgment, Ballot::Grades(grades)) => check_grades(schema, grades),
+        (method, ballot) => Err(BallotError::new(
+            "wrong-ballot",
+            format!("This proposal uses {} and does not accept a '{}' ballot", method.name(), ballot.key()),
+        )),
+    }
+}
+
+/// Abstain and blank mean nothing next to other options.
+fn check_set_aside_alone(schema: &Schema, rule: &'static str, options: &[String]) -> std::result::Result<(), BallotError> {
+    if options.len() > 1
+        && let Some(option) = options.iter().find(|o| schema.is_set_aside(o))
+    {
+        return Err(BallotError::new(rule, format!("'{}' must be the only option on a ballot", option)));
+    }
+    Ok(())
+}
+
+fn check_ranking(schema: &Schema, ranking: &[String]) -> std::result::Result<(), BallotError> {
+    if ranking.is_empty() {
+        return Err(BallotError::new("invalid-ranking", "Ranking is empty".into()));
+    }
+    check_set_aside_alone(schema, "invalid-ranking", ranking)?;
+
+    let mut seen = HashSet::new();
+    for option in ranking {
+        let Some(counted_as) = writein::resolve(schema, option) else {
+            return Err(BallotError::new(
+                "invalid-ranking",
+                format!("Invalid option '{}' in ranking. Allowed: {:?}", option, schema.allowed),
+            ));
+        };
+        if !seen.insert(counted_as) {
+            return Err(BallotError::new(
+                "invalid-ranking",
+                format!("Option '{}' is ranked more than once", option),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_approval(schema: &Schema, approved: &[String]) -> std::result::Result<(), BallotError> {
+    check_set_aside_alone(schema, "invalid-approval", approved)?;
+    let mut seen = HashSet::new();
+    for option in approved {
+        let Some(counted_as) = writein::resolve(schema, option) else {
+            return Err(BallotError::new(
+                "invalid-approval",
+                format!("Invalid option '{}' in approval. Allowed: {:?}", option, schema.allowed),
+            ));
+        };
+        if !seen.insert(counted_as) {
+            return Err(BallotError::new(
+                "invalid-approval",
+                format!("Option '{}' is approved more than once", option),
+            ));
+        }
+    }
+
+    let min = schema.min_approvals.unwrap_or(0);
+    let max = schema.max_approvals.unwrap_or(schema.allowed.len());
+    if approved.len() < min || approved.len() > max {
+        return Err(BallotError::new(
+            "invalid-approval",
+            format!("Approved {} option(s); this proposal requires between {} and {}", approved.len(), min, max),
+        ));
+    }
+    Ok(())
+}
+
+fn check_scores(schema: &Schema, scores: &BTreeMap<String, i32>) -> std::result::Result<(), BallotError> {
+    let (min, max) = schema.score_range();
+    let candidates = schema.candidates();
+
+    for (option, score) in scores {
+        if !candidates.contains(option) {
+            return Err(BallotError::new(
+                "invalid-score",
+                format!("Invalid option '{}' in scores. Allowed: {:?}", option, candidates),
+            ));
+        }
+        if *score < min || *score > max {
+            return Err(BallotError::new(
+                "invalid-score",
+                format!("Score {} for '{}' is outside the range {}..={}", score, option, min, max),
+            ));
+        }
+    }
+
+    if schema.unscored == Unscored::Reject {
+        let missing: Vec<_> = candidates.iter().filter(|o| !scores.contains_key(*o)).collect();
+        if !missing.is_empty() {
+            return Err(BallotError::new(
+                "invalid-score",
+                format!("Every option must be scored; missing {:?}", missing),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_allocation(schema: &Schema, votes: &BTreeMap<String, u32>) -> std::result::Result<(), BallotError> {
+    let candidates = schema.candidates();
+    for option in votes.keys() {
+        if !candidates.contains(option) {
+            return Err(BallotError::new(
+                "invalid-allocation",
+                format!("Invalid option '{}' in allocation. Allowed: {:?}", option, candidates),
+            ));
+        }
+    }
+
+    match cost(&schema.method).of_ballot(votes) {
+        Some(spent) if spent <= schema.credits() as u64 => {}
+        Some(spent) => {
+            return Err(BallotError::new(
+                "over-budget",
+                format!("Allocation costs {} credits; the budget is {}", spent, schema.credits()),
+            ));
+        }
+        None => {
+            return Err(BallotError::new(
+                "over-budget",
+                format!("Allocation costs more credits than can be counted; the budget is {}", schema.credits()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_grades(schema: &Schema, grades: &BTreeMap<String, String>) -> std::result::Result<(), BallotError> {
+    let scale = schema.grades();
+    let candidates = schema.candidates();
+
+    for (option, grade) in grades {
+        if !candidates.contains(option) {
+            return Err(BallotError::new(
+                "invalid-grade",
+                format!("Invalid option '{}' in grades. Allowed: {:?}", option, candidates),
+            ));
+        }
+        if !scale.contains(grade) {
+            return Err(BallotError::new(
+                "invalid-grade",
+                format!("Invalid grade '{}' for '{}'. Grades: {:?}", grade, option, scale),
+            ));
+        }
+    }
+
+    let missing: Vec<_> = candidates.iter().filter(|o| !grades.contains_key(*o)).collect();
+    if !missing.is_empty() {
+        return Err(BallotError::new(
+            "invalid-grade",
+            format!("Every option must be graded; missing {:?}", missing),
+        ));
+    }
+    Ok(())
+}
+
+fn cost(method: &Method) -> credits::Cost {
+    if *method == Method::Quadratic { credits::Cost::Quadratic } else { credits::Cost::Linear }
+}
+
+/// Counts already validated `ballots`, each paired with its voter's
+/// weight, with the built-in `method`. Abstain and blank are not candidates.
+fn count(schema: &Schema, method: &Method, ballots: &[(&Ballot, u64)]) -> Result<Outcome> {
+    let candidates = schema.candidates();
+    Ok(match method {
+        Method::Plurality => {
+            let choices: Vec<(&str, u64)> = ballots
+                .iter()
+                .filter_map(|(b, w)| match b {
+                    Ballot::Choice(c) => Some((c.as_str(), *w)),
+                    _ => None,
+                })
+                .collect();
+            let r = plurality::count(&candidates, &choices);
+            Outcome::new(&r, r.winners.clone())
+        }
+        Method::Irv => {
+            let r = irv::count(&candidates, &rankings(ballots));
+            Outcome::new(&r, r.winner.iter().cloned().collect())
+        }
+        Method::Schulze => {
+            let r = schulze::count(&candidates, &rankings(ballots));
+            Outcome::new(&r, r.ranking.first().cloned().unwrap_or_default())
+        }
+        Method::Borda => {
+            let r = borda::count(&candidates, schema.borda_points, &rankings(ballots));
+            Outcome::new(&r, r.winners.clone())
+        }
+        Method::Stv => {
+            let r = stv::count(&candidates, schema.seats(), &rankings(ballots));
+            Outcome::new(&r, r.elected.clone())
+        }
+        Method::Approval => {
+            let approvals: Vec<(&[String], u64)> = ballots
+                .iter()
+                .filter_map(|(b, w)| match b {
+                    Ballot::Approval(a) => Some((a.as_slice(), *w)),
+                    _ => None,
+                })
+                .collect();
+            let r = approval::count(&candidates, &approvals);
+            Outcome::new(&r, r.winners.clone())
+        }
+        Method::Score => {
+            let scores: Vec<(&BTreeMap<String, i32>, u64)> = ballots
+                .iter()
+                .filter_map(|(b, w)| match b {
+                    Ballot::Scores(s) => Some((s, *w)),
+                    _ => None,
+                })
+                .collect();
+            let (min, _) = schema.score_range();
+            let r = score::count(&candidates, min, schema.unscored, &scores);
+            Outcome::new(&r, r.winners.clone())
+        }
+        Method::Cumulative | Method::Quadratic => {
+            let allocations: Vec<(&BTreeMap<String, u32>, u64)> = ballots
+                .iter()
+                .filter_map(|(b, w)| match b {
+                    Ballot::Allocation(a) => Some((a, *w)),
+                    _ => None,
+                })
+                .collect();
+            let r = credits::count(&candidates, cost(method), schema.credits(), &allocations);
+            Outcome::new(&r, r.winners.clone())
+        }
+        Method::MajorityJudgment => {
+            let scale = schema.grades();
+            let grades: Vec<(Vec<usize>, u64)> = ballots
+                .iter()
+                .filter_map(|(b, w)| match b {
+                    Ballot::Grades(g) => Some((
+                        candidates
+                            .iter()
+                            .map(|o| scale.iter().position(|s| Some(s) == g.get(o)).unwrap_or(0))
+                            .collect(),
+                        *w,
+                    )),
+                    _ => None,
+                })
+                .collect();
+            let r = judgment::count(&candidates, &scale, &grades);
+            Outcome::new(&r, r.ranking.first().cloned().unwrap_or_default())
+        }
+        Method::Custom(name) => {
+            return Err(GitVoteError::Schema(format!("'{}' is not a built-in method", name)));
+        }
+    })
+}
+
+fn rankings<'a>(ballots: &[(&'a Ballot, u64)]) -> Vec<(&'a [String], u64)> {
+    ballots
+        .iter()
+        .filter_map(|(b, w)| match b {
+            Ballot::Ranking(r) => Some((r.as_slice(), *w)),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,107 @@
+//! Cumulative and quadratic voting: each voter spreads a fixed credit
+//! budget over the options and the option with the most votes wins. Under
+//! cumulative voting a vote costs one credit; under quadratic voting `n`
+//! votes on one option cost `n²` credits.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cost {
+    Linear,
+    Quadratic,
+}
+
+impl Cost {
+    /// Credits it takes to place `votes` votes on a single option.
+    pub fn of(self, votes: u32) -> u64 {
+        match self {
+            Cost::Linear => votes as u64,
+            Cost::Quadratic => votes as u64 * votes as u64,
+        }
+    }
+
+    /// Credits a whole ballot spends, or `None` if that does not even fit
+    /// in a `u64`.
+    pub fn of_ballot(self, allocation: &BTreeMap<String, u32>) -> Option<u64> {
+        allocation.values().try_fold(0u64, |spent, v| spent.checked_add(self.of(*v)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionVotes {
+    pub option: String,
+    /// Votes placed on the option across all ballots; wide enough for any
+    /// number of votes times any weight.
+    pub votes: u128,
+    /// Credits voters spent to place those votes.
+    pub credits: u128,
+    /// Weight of the ballots that placed at least one vote on the option.
+    pub supporters: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreditsResult {
+    pub cost: Cost,
+    pub budget: u32,
+    /// Per option, in schema order.
+    pub options: Vec<OptionVotes>,
+    /// Total weight of the ballots counted.
+    pub ballots: u64,
+    /// Option(s) with the most votes; several on a tie.
+    pub winners: Vec<String>,
+}
+
+/// Counts `allocations`, each paired with its voter's weight; a voter's
+/// votes and credits are multiplied by their weight.
+pub fn count(options: &[String], cost: Cost, budget: u32, allocations: &[(&BTreeMap<String, u32>, u64)]) -> CreditsResult {
+    let options: Vec<OptionVotes> = options
+        .iter()
+        .map(|option| {
+            let placed: Vec<(u32, u64)> = allocations
+                .iter()
+                .filter_map(|(a, w)| a.get(option).map(|v| (*v, *w)))
+                .filter(|(v, _)| *v > 0)
+                .collect();
+            OptionVotes {
+                option: option.clone(),
+                votes: placed.iter().map(|(v, w)| *v as u128 * *w as u128).sum(),
+                credits: placed.iter().map(|(v, w)| cost.of(*v) as u128 * *w as u128).sum(),
+                supporters: placed.iter().map(|(_, w)| w).sum(),
+            }
+        })
+        .collect();
+
+    let top = options.iter().map(|o| o.votes).max().unwrap_or(0);
+    let winners = if top == 0 {
+        vec![]
+    } else {
+        options.iter().filter(|o| o.votes == top).map(|o| o.option.clone()).collect()
+    };
+
+    CreditsResult { cost, budget, options, ballots: allocations.iter().map(|(_, w)| w).sum(), winners }
+}
+
+impl fmt::Display for CreditsResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = match self.cost {
+            Cost::Linear => "1 credit per vote",
+            Cost::Quadratic => "n votes cost n² credits",
+        };
+        writeln!(f, "Budget: {} credits per voter ({})", self.budget, rule)?;
+
+        for o in &self.options {
+            writeln!(
+                f,
+                "{}: {} effective votes ({} credits from {} of {} ballots)",
+                o.option, o.votes, o.credits, o.supporters, self.ballots
+            )?;
+        }
+
+        match self.winners.as_slice() {
+            [] => writeln!(f, "No winner: no votes placed"),
+            [winner] => writeln!(f, "Winner: {}", winner),
+            tied => writeln!(f, "Tie between: {}", tied.join(", ")),
+        }
+    }
+}
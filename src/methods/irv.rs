@@ -0,0 +1,100 @@
+//! Instant-runoff voting: repeatedly eliminate the option with the fewest
+//! first preferences and move its ballots to their next choice, until one
+//! option holds a majority of the ballots still in play.
+
+use std::fmt;
+
+/// One counting round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    /// First preferences among continuing options, in schema order.
+    pub counts: Vec<(String, u64)>,
+    /// Ballots with no continuing option left.
+    pub exhausted: u64,
+    /// Option knocked out at the end of this round, if any.
+    pub eliminated: Option<String>,
+    /// Whether several options shared the lowest count.
+    pub tie: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IrvResult {
+    pub rounds: Vec<Round>,
+    pub winner: Option<String>,
+}
+
+/// Runs IRV over `rankings`, each paired with its voter's weight. Ties for
+/// last place eliminate the option listed last in `options`.
+pub fn count(options: &[String], rankings: &[(&[String], u64)]) -> IrvResult {
+    let mut continuing: Vec<String> = options.to_vec();
+    let mut rounds = vec![];
+
+    loop {
+        let mut counts: Vec<(String, u64)> = continuing.iter().map(|o| (o.clone(), 0)).collect();
+        let mut exhausted = 0;
+
+        for (ranking, weight) in rankings {
+            match ranking.iter().find(|o| continuing.contains(o)) {
+                Some(top) => {
+                    if let Some(entry) = counts.iter_mut().find(|(o, _)| o == top) {
+                        entry.1 += weight;
+                    }
+                }
+                None => exhausted += weight,
+            }
+        }
+
+        let active: u64 = counts.iter().map(|(_, c)| c).sum();
+        let leader = counts.iter().max_by_key(|(_, c)| *c).cloned();
+
+        if active == 0 {
+            rounds.push(Round { counts, exhausted, eliminated: None, tie: false });
+            return IrvResult { rounds, winner: None };
+        }
+
+        if let Some((name, votes)) = &leader
+            && (votes * 2 > active || continuing.len() == 1)
+        {
+            let winner = name.clone();
+            rounds.push(Round { counts, exhausted, eliminated: None, tie: false });
+            return IrvResult { rounds, winner: Some(winner) };
+        }
+
+        let lowest = counts.iter().map(|(_, c)| *c).min().unwrap_or(0);
+        let tied: Vec<_> = counts.iter().filter(|(_, c)| *c == lowest).map(|(o, _)| o.clone()).collect();
+        let eliminated = tied.last().cloned().expect("at least one continuing option");
+
+        continuing.retain(|o| *o != eliminated);
+        rounds.push(Round {
+            counts,
+            exhausted,
+            eliminated: Some(eliminated),
+            tie: tied.len() > 1,
+        });
+    }
+}
+
+impl fmt::Display for IrvResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(f, "Round {}:", i + 1)?;
+            for (option, count) in &round.counts {
+                writeln!(f, "  {}: {}", option, count)?;
+            }
+            writeln!(f, "  exhausted: {}", round.exhausted)?;
+
+            if let Some(eliminated) = &round.eliminated {
+                if round.tie {
+                    writeln!(f, "  ✘ {} eliminated (tied for last; later in schema order)", eliminated)?;
+                } else {
+                    writeln!(f, "  ✘ {} eliminated", eliminated)?;
+                }
+            }
+        }
+
+        match &self.winner {
+            Some(winner) => writeln!(f, "Winner: {}", winner),
+            None => writeln!(f, "No winner: no ballots ranked a continuing option"),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Majority Judgment: voters grade every option on an ordered scale, each
+//! option is rated by its median grade, and options sharing a median are
+//! separated by the standard tie-break.
+//!
+//! Medians are lower medians. To break a tie, one median grade is removed
+//! from each tied option and the medians are compared again, repeatedly,
+//! until they differ. That sequence of medians is the option's "majority
+//! value"; options compare by it lexicographically.
+//!
+//! A voter of weight `w` counts as `w` ballots, so a majority value can be
+//! as long as the total weight. It is never spelled out: the medians
+//! removed in turn walk outward from the middle of the sorted grades, one
+//! step below and one above, so each step's grade follows from the
+//! distribution and two majority values are compared a stretch of equal
+//! grades at a time.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionGrades {
+    pub option: String,
+    /// `distribution[g]`: weight of ballots giving grade `g` (index into the scale).
+    pub distribution: Vec<u64>,
+    /// Median grade, `None` when nobody graded the option.
+    pub median: Option<usize>,
+    /// Successive medians as median grades are removed; compared
+    /// lexicographically to order options.
+    pub majority_value: MajorityValue,
+}
+
+/// The sequence of medians met as median grades are removed one at a time,
+/// held as the grade distribution it follows from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MajorityValue {
+    distribution: Vec<u64>,
+    total: u64,
+}
+
+impl MajorityValue {
+    pub fn new(distribution: &[u64]) -> Self {
+        MajorityValue { distribution: distribution.to_vec(), total: distribution.iter().sum() }
+    }
+
+    /// Number of medians in the sequence: the total weight graded.
+    pub fn len(&self) -> u64 {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Median grade after `step` median grades were removed.
+    pub fn get(&self, step: u64) -> Option<usize> {
+        (step < self.total).then(|| self.bucket(self.position(step)).0)
+    }
+
+    /// The first `n` medians.
+    pub fn first(&self, n: u64) -> Vec<usize> {
+        (0..n.min(self.total)).filter_map(|step| self.get(step)).collect()
+    }
+
+    /// First step at which `self` and `other` have different medians, if
+    /// they differ before the shorter one ends.
+    pub fn first_difference(&self, other: &MajorityValue) -> Option<u64> {
+        let end = self.total.min(other.total);
+        let mut step = 0;
+        while step < end {
+            // Until `stretch`, each sequence repeats the grades of its
+            // first two steps from here
+            let stretch = self.stretch(step).min(other.stretch(step)).min(end);
+            for s in step..stretch.min(step + 2) {
+                if self.get(s) != other.get(s) {
+                    return Some(s);
+                }
+            }
+            step = stretch;
+        }
+        None
+    }
+
+    /// Index, among the grades sorted worst first, of the median removed
+    /// at `step`. Steps alternate sides of the lower median `m`, starting
+    /// on the side that keeps the remaining grades balanced.
+    fn position(&self, step: u64) -> u64 {
+        let m = (self.total - 1) / 2;
+        let (even_down, half) = (self.total.is_multiple_of(2), step.div_ceil(2));
+        match (step.is_multiple_of(2), even_down) {
+            (true, true) => m - step / 2,
+            (true, false) => m + step / 2,
+            (false, true) => m + half,
+            (false, false) => m - half,
+        }
+    }
+
+    /// Grade at sorted index `position`, with the first and last sorted
+    /// index holding that grade.
+    fn bucket(&self, position: u64) -> (usize, u64, u64) {
+        let mut start = 0;
+        for (grade, n) in self.distribution.iter().enumerate() {
+            if position < start + n {
+                return (grade, start, start + n - 1);
+            }
+            start += n;
+        }
+        unreachable!("distribution holds {} grades", self.total)
+    }
+
+    /// First step after `step` at which either side of the walk reaches a
+    /// new grade.
+    fn stretch(&self, step: u64) -> u64 {
+        let side = |first: u64| {
+            if first >= self.total {
+                return self.total;
+            }
+            let position = self.position(first);
+            let (_, low, high) = self.bucket(position);
+            let later = if first + 2 < self.total { self.position(first + 2) } else { position };
+            let steps = if later >= position { high - position } else { position - low };
+            first + 2 * (steps + 1)
+        };
+        side(step).min(side(step + 1)).min(self.total)
+    }
+}
+
+impl Ord for MajorityValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.first_difference(other) {
+            Some(step) => self.get(step).cmp(&other.get(step)),
+            None => self.total.cmp(&other.total),
+        }
+    }
+}
+
+impl PartialOrd for MajorityValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JudgmentResult {
+    /// Grade names, worst first.
+    pub scale: Vec<String>,
+    /// Per option, in schema order.
+    pub options: Vec<OptionGrades>,
+    /// Options from best to worst; options in the same group are tied.
+    pub ranking: Vec<Vec<String>>,
+    /// When the top two options shared a median: how many median grades
+    /// had to be removed before they differed.
+    pub tie_broken_after: Option<u64>,
+}
+
+impl JudgmentResult {
+    /// The sole winner, or `None` if several share first place.
+    pub fn winner(&self) -> Option<&str> {
+        match self.ranking.first().map(|g| g.as_slice()) {
+            Some([winner]) => Some(winner),
+            _ => None,
+        }
+    }
+}
+
+/// Counts `grades`, where each ballot gives a scale index per option in
+/// `options` order and is paired with its voter's weight. A voter of
+/// weight `w` counts as `w` identical ballots.
+pub fn count(options: &[String], scale: &[String], grades: &[(Vec<usize>, u64)]) -> JudgmentResult {
+    let options: Vec<OptionGrades> = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            let mut distribution = vec![0u64; scale.len()];
+            for (g, weight) in grades {
+                distribution[g[i]] += weight;
+            }
+
+            let majority_value = MajorityValue::new(&distribution);
+            OptionGrades {
+                option: option.clone(),
+                distribution,
+                median: majority_value.get(0),
+                majority_value,
+            }
+        })
+        .collect();
+
+    let mut order: Vec<&OptionGrades> = options.iter().collect();
+    order.sort_by(|a, b| b.majority_value.cmp(&a.majority_value));
+
+    let mut ranking: Vec<Vec<String>> = vec![];
+    let mut last: Option<&MajorityValue> = None;
+    for o in &order {
+        if last == Some(&o.majority_value) {
+            ranking.last_mut().expect("group exists").push(o.option.clone());
+        } else {
+            ranking.push(vec![o.option.clone()]);
+            last = Some(&o.majority_value);
+        }
+    }
+
+    let tie_broken_after = match order.as_slice() {
+        [first, second, ..] if first.median.is_some() && first.median == second.median => {
+            first.majority_value.first_difference(&second.majority_value)
+        }
+        _ => None,
+    };
+
+    JudgmentResult { scale: scale.to_vec(), options, ranking, tie_broken_after }
+}
+
+impl fmt::Display for JudgmentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Grades: {}", self.scale.join(" < "))?;
+
+        for o in &self.options {
+            let distribution: Vec<String> = self
+                .scale
+                .iter()
+                .zip(&o.distribution)
+                .map(|(grade, n)| format!("{} {}", grade, n))
+                .collect();
+            match o.median {
+                Some(median) => writeln!(
+                    f,
+                    "{}: median {} ({})",
+                    o.option,
+                    self.scale[median],
+                    distribution.join(", ")
+                )?,
+                None => writeln!(f, "{}: not graded", o.option)?,
+            }
+        }
+
+        if let Some(removed) = self.tie_broken_after {
+            writeln!(f, "Tie on median broken after removing {} median grade(s)", removed)?;
+        }
+
+        writeln!(f, "Ranking:")?;
+        for (place, group) in self.ranking.iter().enumerate() {
+            if group.len() > 1 {
+                writeln!(f, "  {}. {} (tied)", place + 1, group.join(", "))?;
+            } else {
+                writeln!(f, "  {}. {}", place + 1, group[0])?;
+            }
+        }
+
+        match self.winner() {
+            Some(winner) => writeln!(f, "Winner: {}", winner),
+            None => writeln!(f, "Tie for first place"),
+        }
+    }
+}
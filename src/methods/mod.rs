@@ -0,0 +1,163 @@
+//! Ballot rules and counting for each voting method a schema can select.
+//!
+//! Every method implements [`VotingMethod`] and is looked up by the name in
+//! `schema.json`'s `method` field. The built-in methods are always
+//! available; programs embedding gitvote can add their own with
+//! [`register`] before running any command.
+
+pub mod approval;
+pub mod borda;
+pub mod builtin;
+pub mod credits;
+pub mod irv;
+pub mod judgment;
+pub mod plurality;
+pub mod schulze;
+pub mod score;
+pub mod stv;
+
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+use crate::block::Block;
+use crate::error::{GitVoteError, Result};
+use crate::schema::{Method, Question, Schema};
+use crate::vote::Ballot;
+use self::builtin::Builtin;
+
+/// Why a ballot does not fit the proposal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BallotError {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl BallotError {
+    fn new(rule: &'static str, message: String) -> Self {
+        BallotError { rule, message }
+    }
+}
+
+/// What counting produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The method's printable result.
+    pub text: String,
+    /// Winning option(s): one per seat, or several on a tie.
+    pub winners: Vec<String>,
+}
+
+impl Outcome {
+    pub fn new(result: impl fmt::Display, winners: Vec<String>) -> Self {
+        Outcome { text: result.to_string(), winners }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// A way of validating and counting ballots.
+pub trait VotingMethod: Send + Sync {
+    /// Name a schema selects this method by, e.g. `"irv"`.
+    fn name(&self) -> &str;
+
+    /// Checks that `ballot` is the kind this method expects and fits the
+    /// schema.
+    fn check_ballot(&self, schema: &Schema, ballot: &Ballot) -> std::result::Result<(), BallotError>;
+
+    /// Counts `blocks`, which hold one ballot per voter that has already
+    /// passed [`VotingMethod::check_ballot`].
+    fn tally(&self, schema: &Schema, blocks: &mut dyn Iterator<Item = &Block>) -> Result<Outcome>;
+}
+
+fn registry() -> &'static RwLock<Vec<Arc<dyn VotingMethod>>> {
+    static REGISTRY: OnceLock<RwLock<Vec<Arc<dyn VotingMethod>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let builtins = Method::BUILTIN
+            .iter()
+            .map(|m| Arc::new(Builtin(m.clone())) as Arc<dyn VotingMethod>)
+            .collect();
+        RwLock::new(builtins)
+    })
+}
+
+/// Makes `method` available to schemas under its name, replacing any
+/// method (built-in or not) already registered under that name.
+pub fn register(method: impl VotingMethod + 'static) {
+    let mut methods = registry().write().expect("method registry poisoned");
+    methods.retain(|m| m.name() != method.name());
+    methods.push(Arc::new(method));
+}
+
+/// The method registered under `name`, if any.
+pub fn lookup(name: &str) -> Option<Arc<dyn VotingMethod>> {
+    let methods = registry().read().expect("method registry poisoned");
+    methods.iter().find(|m| m.name() == name).cloned()
+}
+
+/// Names of every registered method.
+pub fn names() -> Vec<String> {
+    let methods = registry().read().expect("method registry poisoned");
+    methods.iter().map(|m| m.name().to_string()).collect()
+}
+
+fn method_for(schema: &Schema) -> Result<Arc<dyn VotingMethod>> {
+    let name = schema.method.name();
+    lookup(name).ok_or_else(|| {
+        GitVoteError::Schema(format!("Unknown voting method '{}'. Available: {}", name, names().join(", ")))
+    })
+}
+
+/// Checks `ballot` against the schema's method, or each answer against
+/// its question's method.
+pub fn check_ballot(schema: &Schema, ballot: &Ballot) -> std::result::Result<(), BallotError> {
+    if let Some(questions) = &schema.questions {
+        return check_answers(schema, questions, ballot);
+    }
+    match method_for(schema) {
+        Ok(method) => method.check_ballot(schema, ballot),
+        Err(e) => Err(BallotError::new("unknown-method", e.to_string())),
+    }
+}
+
+fn check_answers(schema: &Schema, questions: &[Question], ballot: &Ballot) -> std::result::Result<(), BallotError> {
+    let Ballot::Answers(answers) = ballot else {
+        return Err(BallotError::new(
+            "wrong-ballot",
+            format!("This proposal has several questions and does not accept a '{}' ballot", ballot.key()),
+        ));
+    };
+    if answers.is_empty() {
+        return Err(BallotError::new("invalid-answers", "No question is answered".into()));
+    }
+
+    for (id, answer) in answers {
+        let Some(question) = schema.question(id) else {
+            let ids: Vec<_> = questions.iter().map(|q| q.id.as_str()).collect();
+            return Err(BallotError::new(
+                "invalid-answers",
+                format!("Unknown question '{}'. Questions: {:?}", id, ids),
+            ));
+        };
+        check_ballot(&schema.for_question(question), answer)
+            .map_err(|e| BallotError::new(e.rule, format!("{}: {}", id, e.message)))?;
+    }
+    Ok(())
+}
+
+/// Checks every ballot in `blocks`, then counts them with the schema's
+/// method. Abstentions and blank ballots are left out of the count; the
+/// verdict still counts them toward quorum.
+pub fn tally(schema: &Schema, blocks: &[Block]) -> Result<Outcome> {
+    let method = method_for(schema)?;
+
+    for block in blocks {
+        if let Err(e) = method.check_ballot(schema, &block.ballot) {
+            return Err(GitVoteError::Validation(format!("cannot count ballot '{}': {}", block.ballot, e.message)));
+        }
+    }
+
+    method.tally(schema, &mut blocks.iter().filter(|b| !schema.sets_aside(&b.ballot)))
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluralityResult {
+    /// Votes per option, in schema order.
+    pub counts: Vec<(String, u64)>,
+    /// Option(s) with the most votes; several on a tie.
+    pub winners: Vec<String>,
+}
+
+/// Counts `choices`, each paired with its voter's weight.
+pub fn count(options: &[String], choices: &[(&str, u64)]) -> PluralityResult {
+    let counts: Vec<(String, u64)> = options
+        .iter()
+        .map(|option| {
+            let votes = choices.iter().filter(|(c, _)| *c == option).map(|(_, w)| w).sum();
+            (option.clone(), votes)
+        })
+        .collect();
+
+    let top = counts.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let winners = if top == 0 {
+        vec![]
+    } else {
+        counts.iter().filter(|(_, n)| *n == top).map(|(o, _)| o.clone()).collect()
+    };
+
+    PluralityResult { counts, winners }
+}
+
+impl fmt::Display for PluralityResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (choice, count) in &self.counts {
+            writeln!(f, "{} votes: {}", choice, count)?;
+        }
+        Ok(())
+    }
+}
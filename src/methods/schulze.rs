@@ -0,0 +1,148 @@
+//! Schulze method: a Condorcet-consistent count over ranked ballots. Builds
+//! the pairwise preference matrix, finds the strongest path between every
+//! pair of options, and orders options by which paths are stronger.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchulzeResult {
+    pub options: Vec<String>,
+    /// `pairwise[i][j]`: weight of ballots preferring option `i` over option `j`.
+    pub pairwise: Vec<Vec<u64>>,
+    /// `paths[i][j]`: strength of the strongest path from `i` to `j`.
+    pub paths: Vec<Vec<u64>>,
+    /// Options from best to worst; options in the same group are tied.
+    pub ranking: Vec<Vec<String>>,
+    /// Option that beats every other head to head, if there is one.
+    pub condorcet_winner: Option<String>,
+}
+
+impl SchulzeResult {
+    /// The sole Schulze winner, or `None` if several share first place.
+    pub fn winner(&self) -> Option<&str> {
+        match self.ranking.first().map(|g| g.as_slice()) {
+            Some([winner]) => Some(winner),
+            _ => None,
+        }
+    }
+}
+
+/// Counts `rankings`, each paired with its voter's weight. Options a ballot
+/// leaves out are treated as tied below every option it ranks.
+pub fn count(options: &[String], rankings: &[(&[String], u64)]) -> SchulzeResult {
+    let n = options.len();
+    let mut pairwise = vec![vec![0u64; n]; n];
+
+    for (ranking, weight) in rankings {
+        let position = |option: &String| ranking.iter().position(|r| r == option).unwrap_or(usize::MAX);
+        for i in 0..n {
+            for j in 0..n {
+                if i != j && position(&options[i]) < position(&options[j]) {
+                    pairwise[i][j] += weight;
+                }
+            }
+        }
+    }
+
+    let mut paths = vec![vec![0u64; n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            if i != j && pairwise[i][j] > pairwise[j][i] {
+                paths[i][j] = pairwise[i][j];
+            }
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            for j in 0..n {
+                if i != j && i != k && j != k {
+                    paths[i][j] = paths[i][j].max(paths[i][k].min(paths[k][j]));
+                }
+            }
+        }
+    }
+
+    // Schulze's relation is transitive, so counting how many options each
+    // one beats orders them consistently.
+    let wins: Vec<usize> = (0..n)
+        .map(|i| (0..n).filter(|&j| i != j && paths[i][j] > paths[j][i]).count())
+        .collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|a, b| wins[*b].cmp(&wins[*a]));
+
+    let mut ranking: Vec<Vec<String>> = vec![];
+    let mut last_wins = None;
+    for i in order {
+        if last_wins == Some(wins[i]) {
+            ranking.last_mut().expect("group exists").push(options[i].clone());
+        } else {
+            ranking.push(vec![options[i].clone()]);
+            last_wins = Some(wins[i]);
+        }
+    }
+
+    let condorcet_winner = (0..n)
+        .find(|&i| (0..n).all(|j| i == j || pairwise[i][j] > pairwise[j][i]))
+        .map(|i| options[i].clone());
+
+    SchulzeResult {
+        options: options.to_vec(),
+        pairwise,
+        paths,
+        ranking,
+        condorcet_winner,
+    }
+}
+
+fn write_matrix(f: &mut fmt::Formatter<'_>, options: &[String], matrix: &[Vec<u64>]) -> fmt::Result {
+    let width = options.iter().map(|o| o.chars().count()).max().unwrap_or(1).max(3);
+
+    write!(f, "  {:width$}", "")?;
+    for option in options {
+        write!(f, " {:>width$}", option)?;
+    }
+    writeln!(f)?;
+
+    for (i, option) in options.iter().enumerate() {
+        write!(f, "  {:width$}", option)?;
+        for (j, value) in matrix[i].iter().enumerate() {
+            if i == j {
+                write!(f, " {:>width$}", "-")?;
+            } else {
+                write!(f, " {:>width$}", value)?;
+            }
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for SchulzeResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pairwise preferences (row preferred over column):")?;
+        write_matrix(f, &self.options, &self.pairwise)?;
+        writeln!(f)?;
+        writeln!(f, "Strongest paths:")?;
+        write_matrix(f, &self.options, &self.paths)?;
+        writeln!(f)?;
+
+        match &self.condorcet_winner {
+            Some(winner) => writeln!(f, "Condorcet winner: {}", winner)?,
+            None => writeln!(f, "No Condorcet winner; Schulze resolves the cycle")?,
+        }
+
+        writeln!(f, "Ranking:")?;
+        for (place, group) in self.ranking.iter().enumerate() {
+            if group.len() > 1 {
+                writeln!(f, "  {}. {} (tied)", place + 1, group.join(", "))?;
+            } else {
+                writeln!(f, "  {}. {}", place + 1, group[0])?;
+            }
+        }
+
+        match self.winner() {
+            Some(winner) => writeln!(f, "Winner: {}", winner),
+            None => writeln!(f, "Tie for first place"),
+        }
+    }
+}
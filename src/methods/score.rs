@@ -0,0 +1,83 @@
+//! Score (range) voting: voters score options within a range and the
+//! option with the highest mean score wins.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::schema::Unscored;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionScore {
+    pub option: String,
+    pub total: i128,
+    /// Weight of the ballots that contributed a score to this option.
+    pub scored_by: u64,
+    /// `None` when nobody scored the option.
+    pub mean: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreResult {
+    /// Per option, in schema order.
+    pub scores: Vec<OptionScore>,
+    /// Total weight of the ballots counted.
+    pub ballots: u64,
+    /// Option(s) with the highest mean; several on a tie.
+    pub winners: Vec<String>,
+}
+
+/// Totals `ballots`, each paired with its voter's weight. Options a ballot
+/// leaves out are scored as `min` or skipped, according to `unscored`.
+pub fn count(options: &[String], min: i32, unscored: Unscored, ballots: &[(&BTreeMap<String, i32>, u64)]) -> ScoreResult {
+    let scores: Vec<OptionScore> = options
+        .iter()
+        .map(|option| {
+            let mut total = 0i128;
+            let mut scored_by = 0u64;
+
+            for (ballot, weight) in ballots {
+                let score = match (ballot.get(option), unscored) {
+                    (Some(score), _) => Some(*score),
+                    (None, Unscored::Min) => Some(min),
+                    (None, _) => None,
+                };
+                if let Some(score) = score {
+                    total += score as i128 * *weight as i128;
+                    scored_by += weight;
+                }
+            }
+
+            let mean = (scored_by > 0).then(|| total as f64 / scored_by as f64);
+            OptionScore { option: option.clone(), total, scored_by, mean }
+        })
+        .collect();
+
+    let best = scores.iter().filter_map(|s| s.mean).fold(f64::NEG_INFINITY, f64::max);
+    let winners = scores
+        .iter()
+        .filter(|s| s.mean == Some(best))
+        .map(|s| s.option.clone())
+        .collect();
+
+    ScoreResult { scores, ballots: ballots.iter().map(|(_, w)| w).sum(), winners }
+}
+
+impl fmt::Display for ScoreResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for s in &self.scores {
+            match s.mean {
+                Some(mean) => writeln!(
+                    f,
+                    "{}: total {}, mean {:.2} ({} of {} ballots)",
+                    s.option, s.total, mean, s.scored_by, self.ballots
+                )?,
+                None => writeln!(f, "{}: not scored", s.option)?,
+            }
+        }
+
+        match self.winners.as_slice() {
+            [] => writeln!(f, "No winner: no scores"),
+            [winner] => writeln!(f, "Winner: {}", winner),
+            tied => writeln!(f, "Tie between: {}", tied.join(", ")),
+        }
+    }
+}
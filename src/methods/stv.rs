@@ -0,0 +1,167 @@
+//! Single Transferable Vote for electing several seats from ranked ballots.
+//!
+//! - Quota: Droop, `floor(ballots / (seats + 1)) + 1`, fixed for the count,
+//!   where `ballots` is their total weight.
+//! - Surplus transfer: Weighted Inclusive Gregory. When an option reaches
+//!   the quota, every ballot currently counting for it moves on to its next
+//!   continuing preference at `weight * surplus / total`.
+//! - Otherwise the option with the fewest votes is eliminated and its
+//!   ballots move on at their current weight. A tie for last eliminates
+//!   the option listed last in the schema.
+//! - Once the remaining options exactly fill the open seats, all of them
+//!   are elected.
+
+use std::fmt;
+
+/// Fractions below this are treated as rounding noise when comparing.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Election {
+    pub option: String,
+    pub votes: f64,
+    pub surplus: f64,
+    /// Weight multiplier applied to the ballots passed on.
+    pub transfer_factor: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Round {
+    /// Votes held by each continuing option at the start of the round.
+    pub counts: Vec<(String, f64)>,
+    /// Weight of ballots with no continuing preference left.
+    pub exhausted: f64,
+    pub elected: Vec<Election>,
+    pub eliminated: Option<String>,
+    /// Options elected without reaching quota because they filled the
+    /// remaining seats.
+    pub filled: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StvResult {
+    pub seats: usize,
+    pub quota: u64,
+    pub rounds: Vec<Round>,
+    /// In order of election.
+    pub elected: Vec<String>,
+}
+
+/// Counts `rankings`, each paired with its voter's weight, which is the
+/// ballot's starting value before any surplus transfer.
+pub fn count(options: &[String], seats: usize, rankings: &[(&[String], u64)]) -> StvResult {
+    let mut weights: Vec<f64> = rankings.iter().map(|(_, w)| *w as f64).collect();
+    let valid: u64 = rankings.iter().filter(|(r, _)| !r.is_empty()).map(|(_, w)| w).sum();
+    let quota = valid / (seats as u64 + 1) + 1;
+
+    let mut hopeful: Vec<String> = options.to_vec();
+    let mut elected: Vec<String> = vec![];
+    let mut rounds = vec![];
+
+    while elected.len() < seats && !hopeful.is_empty() {
+        let top: Vec<Option<&String>> = rankings
+            .iter()
+            .map(|(r, _)| r.iter().find(|o| hopeful.contains(o)))
+            .collect();
+
+        let mut counts: Vec<(String, f64)> = hopeful.iter().map(|o| (o.clone(), 0.0)).collect();
+        let mut exhausted = 0.0;
+        for (choice, weight) in top.iter().zip(&weights) {
+            match choice {
+                Some(option) => {
+                    if let Some(entry) = counts.iter_mut().find(|(o, _)| o == *option) {
+                        entry.1 += weight;
+                    }
+                }
+                None => exhausted += weight,
+            }
+        }
+
+        let mut round = Round {
+            counts: counts.clone(),
+            exhausted,
+            elected: vec![],
+            eliminated: None,
+            filled: vec![],
+        };
+
+        if hopeful.len() <= seats - elected.len() {
+            round.filled = hopeful.clone();
+            elected.append(&mut hopeful);
+            rounds.push(round);
+            break;
+        }
+
+        let mut reached: Vec<(String, f64)> = counts
+            .iter()
+            .filter(|(_, v)| *v + EPSILON >= quota as f64)
+            .cloned()
+            .collect();
+        reached.sort_by(|a, b| b.1.total_cmp(&a.1));
+        reached.truncate(seats - elected.len());
+
+        if reached.is_empty() {
+            let lowest = counts.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+            let loser = counts
+                .iter()
+                .rev()
+                .find(|(_, v)| (*v - lowest).abs() < EPSILON)
+                .map(|(o, _)| o.clone())
+                .expect("at least one hopeful option");
+            hopeful.retain(|o| *o != loser);
+            round.eliminated = Some(loser);
+        } else {
+            for (option, votes) in reached {
+                let surplus = (votes - quota as f64).max(0.0);
+                let factor = if votes > 0.0 { surplus / votes } else { 0.0 };
+
+                for (choice, weight) in top.iter().zip(weights.iter_mut()) {
+                    if *choice == Some(&option) {
+                        *weight *= factor;
+                    }
+                }
+
+                hopeful.retain(|o| *o != option);
+                elected.push(option.clone());
+                round.elected.push(Election { option, votes, surplus, transfer_factor: factor });
+            }
+        }
+
+        rounds.push(round);
+    }
+
+    StvResult { seats, quota, rounds, elected }
+}
+
+impl fmt::Display for StvResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Seats: {}, Droop quota: {}", self.seats, self.quota)?;
+
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(f, "Round {}:", i + 1)?;
+            for (option, votes) in &round.counts {
+                writeln!(f, "  {}: {:.4}", option, votes)?;
+            }
+            writeln!(f, "  exhausted: {:.4}", round.exhausted)?;
+
+            for e in &round.elected {
+                writeln!(
+                    f,
+                    "  ✔ {} elected with {:.4}; surplus {:.4} transferred at {:.4} per ballot",
+                    e.option, e.votes, e.surplus, e.transfer_factor
+                )?;
+            }
+            if let Some(eliminated) = &round.eliminated {
+                writeln!(f, "  ✘ {} eliminated; ballots transferred at current weight", eliminated)?;
+            }
+            for option in &round.filled {
+                writeln!(f, "  ✔ {} elected to fill a remaining seat", option)?;
+            }
+        }
+
+        if self.elected.len() < self.seats {
+            writeln!(f, "Only {} of {} seats could be filled", self.elected.len(), self.seats)?;
+        }
+        writeln!(f, "Elected: {}", self.elected.join(", "))
+    }
+}
@@ -0,0 +1,124 @@
+//! Multi-question proposals: splitting them into their questions for
+//! counting, and reading answers typed by the voter.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use crate::block::Block;
+use crate::error::{GitVoteError, Result};
+use crate::schema::{Method, Question, Schema};
+use crate::vote::Ballot;
+use crate::writein::{self, WriteIn};
+
+/// One question as counted.
+pub struct Part<'a> {
+    /// `None` for a single-question proposal.
+    pub question: Option<&'a Question>,
+    /// What the question is decided by, its write-ins included as options.
+    pub rules: Schema,
+    /// Blocks answering the question, each carrying just that answer with
+    /// write-ins merged.
+    pub blocks: Vec<Block>,
+    pub write_ins: Vec<WriteIn>,
+}
+
+/// Splits the proposal into the questions to count. A single-question
+/// proposal is one part.
+pub fn split<'a>(schema: &'a Schema, blocks: &[Block]) -> Vec<Part<'a>> {
+    let part = |question, rules: &Schema, answers: &[Block]| {
+        let (rules, blocks, write_ins) = writein::merge(rules, answers);
+        Part { question, rules, blocks, write_ins }
+    };
+    let Some(questions) = &schema.questions else {
+        return vec![part(None, schema, blocks)];
+    };
+
+    questions
+        .iter()
+        .map(|question| {
+            let answers = blocks
+                .iter()
+                .filter_map(|block| match &block.ballot {
+                    Ballot::Answers(answers) => answers.get(&question.id).map(|answer| Block {
+                        ballot: answer.clone(),
+                        ..block.clone()
+                    }),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            part(Some(question), &schema.for_question(question), &answers)
+        })
+        .collect()
+}
+
+/// Reads an answer to a question decided by `schema` as the ballot its
+/// method expects: `yes`, `a,b,c` for rankings and approvals, or
+/// `a=5,b=2` for scores, allocations and grades.
+pub fn parse_answer(schema: &Schema, text: &str) -> Result<Ballot> {
+    let list = || text.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect();
+
+    Ok(match schema.method {
+        Method::Irv | Method::Schulze | Method::Stv | Method::Borda => Ballot::Ranking(list()),
+        Method::Approval => Ballot::Approval(list()),
+        Method::Score => Ballot::Scores(pairs(text)?),
+        Method::Cumulative | Method::Quadratic => Ballot::Allocation(pairs(text)?),
+        Method::MajorityJudgment => Ballot::Grades(pairs(text)?),
+        Method::Plurality | Method::Custom(_) => Ballot::Choice(text.trim().to_string()),
+    })
+}
+
+fn pairs<T: std::str::FromStr>(text: &str) -> Result<BTreeMap<String, T>> {
+    text.split(',')
+        .map(|pair| {
+            let (option, value) = pair
+                .split_once('=')
+                .ok_or_else(|| GitVoteError::Usage(format!("expected option=value, got '{}'", pair.trim())))?;
+            let value = value
+                .trim()
+                .parse()
+                .map_err(|_| GitVoteError::Usage(format!("'{}' is not a valid value for '{}'", value.trim(), option.trim())))?;
+            Ok((option.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// What to type for a question decided by `method`.
+fn hint(method: &Method) -> &'static str {
+    match method {
+        Method::Irv | Method::Schulze | Method::Stv | Method::Borda => "options in order of preference, e.g. a,b,c",
+        Method::Approval => "every option you approve of, e.g. a,b",
+        Method::Score => "a score per option, e.g. a=5,b=2",
+        Method::Cumulative | Method::Quadratic => "votes per option, e.g. a=3,b=1",
+        Method::MajorityJudgment => "a grade per option, e.g. a=Good,b=Poor",
+        Method::Plurality | Method::Custom(_) => "one option",
+    }
+}
+
+/// Puts every question to the voter on `output` and reads the answers from
+/// `input`. An empty line skips the question.
+pub fn ask(schema: &Schema, mut input: impl BufRead, mut output: impl Write) -> Result<BTreeMap<String, Ballot>> {
+    let mut answers = BTreeMap::new();
+
+    for question in schema.questions.iter().flatten() {
+        let rules = schema.for_question(question);
+        writeln!(output, "{}: {}", question.id, question.text.as_deref().unwrap_or(""))?;
+        let options: Vec<String> = rules
+            .allowed
+            .iter()
+            .map(|o| match rules.label(o) {
+                label if label == o => o.clone(),
+                label => format!("{} ({})", o, label),
+            })
+            .collect();
+        writeln!(output, "  Options: {}", options.join(", "))?;
+        write!(output, "  Your answer ({}; empty to skip): ", hint(&rules.method))?;
+        output.flush()?;
+
+        let mut line = String::new();
+        input.read_line(&mut line)?;
+        if !line.trim().is_empty() {
+            answers.insert(question.id.clone(), parse_answer(&rules, line.trim())?);
+        }
+    }
+
+    Ok(answers)
+}
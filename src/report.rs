@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::annotate::CheckReport;
+use crate::chain::{self, check_chain};
+use crate::error::Result;
+use crate::git::{commit_adding, commit_signature, files_added_since, CommitSignature};
+use crate::schema::load_schema;
+use crate::utils::generate_fake_signature;
+use crate::validate::check_votes;
+use crate::tally;
+use crate::vote::{load_votes, vote_paths, Vote};
+
+/// One vote file touched by the pull request being reported on.
+#[derive(Debug)]
+pub struct VoteSummary {
+    pub file: PathBuf,
+    pub voter: Option<String>,
+    pub signature_valid: bool,
+    pub commit_signature: Option<CommitSignature>,
+}
+
+/// Everything a reviewer needs to judge a vote PR at a glance.
+#[derive(Debug)]
+pub struct PrReport {
+    pub validation: CheckReport,
+    pub votes: Vec<VoteSummary>,
+    pub turnout: usize,
+    /// Counting result so far; `None` when the proposal keeps the tally
+    /// hidden until close.
+    pub tally: Option<String>,
+}
+
+/// Collects the report. With `base`, only votes added since that ref are
+/// listed as this PR's votes; otherwise every vote is.
+pub fn build_report(base: Option<&str>) -> Result<PrReport> {
+    let schema = load_schema()?;
+    let mut validation = check_votes(&schema)?;
+    validation.merge(check_chain()?);
+
+    let pr_files = match base {
+        Some(base) => files_added_since(base, "votes")?,
+        None => vote_paths()?,
+    };
+
+    let mut votes = vec![];
+    for file in pr_files {
+        let vote: Option<Vote> = fs::read_to_string(&file)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok());
+
+        let commit_signature = match commit_adding(&file)? {
+            Some(sha) => Some(commit_signature(&sha)?),
+            None => None,
+        };
+
+        votes.push(VoteSummary {
+            signature_valid: vote
+                .as_ref()
+                .is_some_and(|v| v.signature == generate_fake_signature(&v.voter, &v.ballot.canonical())),
+            voter: vote.map(|v| v.voter),
+            file,
+            commit_signature,
+        });
+    }
+
+    // Count what `gitvote tally` would count if the chain were built now
+    let (turnout, result) = match chain::current(&schema).and_then(|blocks| tally::count(&schema, blocks)) {
+        Ok(count) => {
+            let text: Result<String> =
+                tally::outcomes(&schema, &count).map(|parts| parts.into_iter().map(|(_, _, text)| text).collect());
+            (count.cast.len(), text)
+        }
+        Err(e) => {
+            let voters: HashSet<String> = load_votes()?.into_iter().map(|(_, vote)| vote.voter).collect();
+            (voters.len(), Ok(format!("Cannot be counted yet: {}\n", e)))
+        }
+    };
+    let tally = if schema.public_tally { Some(result?) } else { None };
+
+    Ok(PrReport {
+        validation,
+        votes,
+        turnout,
+        tally,
+    })
+}
+
+/// Prints the report, as Markdown if asked, to `output` or stdout.
+pub fn print_report(base: Option<&str>, markdown: bool, output: Option<&Path>) -> Result<()> {
+    let report = build_report(base)?;
+    let rendered = if markdown { render_markdown(&report) } else { render_text(&report) };
+
+    match output {
+        Some(path) => fs::write(path, rendered)?,
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+pub fn render_markdown(report: &PrReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "## 🗳️ GitVote report");
+    let _ = writeln!(out);
+
+    if report.validation.is_clean() {
+        let _ = writeln!(out, "**Validation:** ✅ passed");
+    } else {
+        let _ = writeln!(out, "**Validation:** ❌ {} problem(s)", report.validation.findings.len());
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| File | Line | Rule | Problem |");
+        let _ = writeln!(out, "|------|------|------|---------|");
+        for f in &report.validation.findings {
+            let line = f.line.map(|l| l.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "| {} | {} | `{}` | {} |",
+                md_code(&f.file.to_string_lossy()),
+                line,
+                f.rule,
+                md_cell(&f.message)
+            );
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Votes in this PR");
+    let _ = writeln!(out);
+
+    if report.votes.is_empty() {
+        let _ = writeln!(out, "_No vote files added._");
+    } else {
+        let _ = writeln!(out, "| Voter | Vote signature | Commit signature |");
+        let _ = writeln!(out, "|-------|----------------|------------------|");
+        for v in &report.votes {
+            let voter = match &v.voter {
+                Some(voter) => md_code(voter),
+                None => format!("_unreadable_ {}", md_code(&v.file.to_string_lossy())),
+            };
+            let vote_sig = if v.signature_valid { "✅ verified" } else { "❌ mismatch" };
+            let _ = writeln!(out, "| {} | {} | {} |", voter, vote_sig, describe_commit_signature(&v.commit_signature, true));
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Turnout");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "**{}** voter(s) so far.", report.turnout);
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Running tally");
+    let _ = writeln!(out);
+
+    match &report.tally {
+        Some(_) if report.turnout == 0 => {
+            let _ = writeln!(out, "_No votes yet._");
+        }
+        Some(tally) => {
+            let _ = writeln!(out, "```");
+            let _ = write!(out, "{}", tally);
+            let _ = writeln!(out, "```");
+        }
+        None => {
+            let _ = writeln!(out, "_Hidden until the vote closes._");
+        }
+    }
+
+    out
+}
+
+pub fn render_text(report: &PrReport) -> String {
+    let mut out = String::new();
+
+    if report.validation.is_clean() {
+        let _ = writeln!(out, "✔ Validation passed");
+    } else {
+        let _ = writeln!(out, "✘ Validation failed:");
+        for f in &report.validation.findings {
+            let _ = writeln!(out, "  {}: {}", f.file.display(), f.message);
+        }
+    }
+
+    for v in &report.votes {
+        let _ = writeln!(
+            out,
+            "Vote {}: voter {}, signature {}, commit {}",
+            v.file.display(),
+            v.voter.as_deref().unwrap_or("<unreadable>"),
+            if v.signature_valid { "verified" } else { "mismatch" },
+            describe_commit_signature(&v.commit_signature, false)
+        );
+    }
+
+    let _ = writeln!(out, "Turnout: {}", report.turnout);
+
+    if let Some(tally) = &report.tally {
+        let _ = write!(out, "{}", tally);
+    }
+
+    out
+}
+
+fn describe_commit_signature(sig: &Option<CommitSignature>, markdown: bool) -> String {
+    let (icon, text) = match sig {
+        Some(CommitSignature::Good(fpr)) => ("✅", format!("good ({})", fpr)),
+        Some(CommitSignature::Unverified(why)) => ("⚠️", format!("unverified: {}", why)),
+        Some(CommitSignature::Bad) => ("❌", "bad".to_string()),
+        Some(CommitSignature::Unsigned) => ("⚠️", "unsigned".to_string()),
+        None => ("⚠️", "not committed".to_string()),
+    };
+
+    if markdown { format!("{} {}", icon, text) } else { text }
+}
+
+/// Inline code span, safe inside a table cell.
+fn md_code(s: &str) -> String {
+    format!("`{}`", s.replace('`', "'").replace('|', "\\|"))
+}
+
+fn md_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('<', "&lt;").replace('>', "&gt;").replace('\n', " ")
+}
@@ -1,22 +1,834 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
-use std::path::Path;
-use std::error::Error;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
 use serde::{ Deserialize, Serialize };
+use sha2::{Digest, Sha256};
+use crate::error::{GitVoteError, Result};
+use crate::methods;
+use crate::vote::Ballot;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Newest schema format this version of gitvote reads.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Largest weight a voter may carry. It keeps every sum of weights, and
+/// the majority checks on them, well inside a `u64` for any electorate a
+/// repository can hold.
+pub const MAX_WEIGHT: u64 = 1_000_000_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Schema {
+    /// Schema format version; 1 (the minimal form) if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
+    /// Identifier of the proposal, e.g. `RFC-042`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal_id: Option<String>,
+
+    /// Short title shown to voters and in the tally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// What is being decided, in a sentence or two
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Options to vote on; empty when the proposal has `questions`
+    #[serde(default)]
     pub allowed: Vec<String>,
+
+    /// Options with a label and description each; `load_schema` fills
+    /// `allowed` from their names when it is omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<OptionInfo>>,
+
+    /// Independent questions decided by the same vote, each with its own
+    /// options, method and decision rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub questions: Option<Vec<Question>>,
+
+    /// Whether voters may name options not in `allowed`
+    #[serde(default)]
+    pub write_ins: bool,
+
+    /// How ballots are cast and counted; plurality if omitted
+    #[serde(default)]
+    pub method: Method,
+
+    /// Fewest options an approval ballot may approve (default 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_approvals: Option<usize>,
+
+    /// Most options an approval ballot may approve (default: all)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_approvals: Option<usize>,
+
+    /// Lowest and highest score for score voting (default `[0, 5]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_range: Option<(i32, i32)>,
+
+    /// What a score ballot that skips an option means
+    #[serde(default)]
+    pub unscored: Unscored,
+
+    /// Credits each voter may spend under cumulative or quadratic voting
+    /// (default 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credits: Option<u32>,
+
+    /// Grade names for Majority Judgment, worst first (default
+    /// Reject, Poor, Acceptable, Good, Very Good, Excellent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grades: Option<Vec<String>>,
+
+    /// How Borda awards points per place
+    #[serde(default)]
+    pub borda_points: BordaPoints,
+
+    /// Number of options STV elects (default 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seats: Option<usize>,
+
+    /// Voting weight per voter, e.g. team size or share count; every
+    /// ballot counts once if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Weights>,
+
+    /// Weight of voters the ledger does not list (default 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_weight: Option<u64>,
+
+    /// The ledger read from `weights.file` by `load_schema`
+    #[serde(skip)]
+    pub weight_ledger: Option<BTreeMap<String, u64>>,
+
+    /// The voter roll: everyone entitled to vote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voters: Option<Vec<String>>,
+
+    /// Who may vote; anyone if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<Eligibility>,
+
+    /// Turnout needed for the result to stand; none if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<Quorum>,
+
+    /// Share of the votes the winner needs for the proposal to pass
+    #[serde(default)]
+    pub majority: Majority,
+
+    /// Allowed option that means "I abstain": counted toward quorum, not
+    /// toward the majority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abstain: Option<String>,
+
+    /// Allowed option for a deliberately blank ballot: counted toward
+    /// quorum, never toward the majority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blank: Option<String>,
+
+    /// Allowed option meaning "none of the above": counted like any other
+    /// option, and if it wins the vote must be re-run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nota: Option<String>,
+
+    /// Whether abstentions count against the proposal
+    #[serde(default)]
+    pub abstentions: Abstentions,
+
+    /// When the voting window opens; open from the start if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opens_at: Option<DateTime<Utc>>,
+
+    /// When the voting window closes; never if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closes_at: Option<DateTime<Utc>>,
+
+    /// What happens when a voter votes more than once
+    #[serde(default)]
+    pub revote: Revote,
+
+    /// How a tie for the win is broken; reported as a tie if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tie_break: Option<TieBreakPolicy>,
+
+    /// Who may cast the deciding vote under the `casting_vote` policy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub casting_voter: Option<String>,
+
+    /// Whether the running tally may be shown before the vote closes
+    #[serde(default)]
+    pub public_tally: bool,
+}
+
+/// One question of a multi-question proposal. Apart from `id` and `text`
+/// it takes the same fields as a single-question schema: `allowed`,
+/// `method` and its settings, and the decision rules.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Question {
+    /// Short name voters answer the question by, e.g. `budget`
+    pub id: String,
+
+    /// The question as put to voters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(flatten)]
+    pub rules: Schema,
+}
+
+/// An option described for voters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OptionInfo {
+    /// What ballots name the option by, as in `allowed`
+    pub name: String,
+
+    /// Human-readable name, e.g. `Adopt the budget`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Restrictions on who may vote. A voter must meet all of them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Eligibility {
+    /// Only voters on the `voters` roll, listed by voter ID or email
+    #[serde(default)]
+    pub roll_only: bool,
+
+    /// Only voters with an email address at one of these domains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_domains: Option<Vec<String>>,
+}
+
+/// Voting methods a proposal can use. Stored in the schema by name; any
+/// name that is not built in refers to a method registered with
+/// [`crate::methods::register`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(from = "String", into = "String")]
+pub enum Method {
+    /// One choice per voter; most votes wins
+    #[default]
+    Plurality,
+
+    /// Ranked ballots counted by instant-runoff
+    Irv,
+
+    /// Approve any number of options; most approvals wins
+    Approval,
+
+    /// Score every option within a range; highest mean score wins
+    Score,
+
+    /// Ranked ballots counted pairwise with the Schulze method
+    Schulze,
+
+    /// Ranked ballots electing several options by single transferable vote
+    Stv,
+
+    /// Ranked ballots awarding points by place
+    Borda,
+
+    /// Spread a credit budget over options, one credit per vote
+    Cumulative,
+
+    /// Spread a credit budget over options; n votes on one option cost n²
+    Quadratic,
+
+    /// Grade every option; highest median grade wins
+    MajorityJudgment,
+
+    /// A method registered by the program embedding gitvote
+    Custom(String),
+}
+
+impl Method {
+    /// Every method that ships with gitvote.
+    pub const BUILTIN: [Method; 10] = [
+        Method::Plurality,
+        Method::Irv,
+        Method::Approval,
+        Method::Score,
+        Method::Schulze,
+        Method::Stv,
+        Method::Borda,
+        Method::Cumulative,
+        Method::Quadratic,
+        Method::MajorityJudgment,
+    ];
+
+    /// Name used for this method in `schema.json`.
+    pub fn name(&self) -> &str {
+        match self {
+            Method::Plurality => "plurality",
+            Method::Irv => "irv",
+            Method::Approval => "approval",
+            Method::Score => "score",
+            Method::Schulze => "schulze",
+            Method::Stv => "stv",
+            Method::Borda => "borda",
+            Method::Cumulative => "cumulative",
+            Method::Quadratic => "quadratic",
+            Method::MajorityJudgment => "majority_judgment",
+            Method::Custom(name) => name,
+        }
+    }
+}
+
+impl From<String> for Method {
+    fn from(name: String) -> Self {
+        Method::BUILTIN
+            .into_iter()
+            .find(|m| m.name() == name)
+            .unwrap_or(Method::Custom(name))
+    }
+}
+
+impl From<Method> for String {
+    fn from(method: Method) -> Self {
+        method.name().to_string()
+    }
+}
+
+/// How score voting treats options a ballot leaves unscored.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Unscored {
+    /// Ballots must score every option
+    #[default]
+    Reject,
+
+    /// Count a missing score as the lowest score in the range
+    Min,
+
+    /// Leave the option out of that ballot's count
+    Ignore,
+}
+
+/// Which of a voter's votes counts when they vote more than once, ordered
+/// by the commit that added each vote.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Revote {
+    /// A second vote is invalid
+    #[default]
+    Reject,
+
+    /// The first vote counts; later ones are superseded
+    FirstWins,
+
+    /// The latest vote counts, so voters may change their mind
+    LastWins,
+}
+
+/// Where voter weights come from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Weights {
+    /// A separate JSON file mapping voter to weight, fixed when the vote
+    /// opens by pinning its SHA-256
+    File { file: PathBuf, sha256: Option<String> },
+
+    /// Voter to weight, written into the schema
+    Ledger(BTreeMap<String, u64>),
+}
+
+/// Minimum turnout, counted in voters (or weight, in a weighted vote).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Quorum {
+    /// At least this many votes
+    Voters(u64),
+
+    /// At least this fraction of the voter roll, e.g. `0.5`
+    Fraction(f64),
+}
+
+/// Share of the votes the winner needs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Majority {
+    /// More than half
+    #[default]
+    Simple,
+
+    /// At least two thirds
+    TwoThirds,
+
+    /// Every vote
+    Unanimous,
+}
+
+/// How abstentions count toward the majority. They always count toward
+/// quorum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Abstentions {
+    /// Leave them out of the votes the majority is taken over
+    #[default]
+    Exclude,
+
+    /// Count them as votes against
+    Against,
+}
+
+/// Ways to pick a single winner from options tied for first place.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreakPolicy {
+    /// The tied option preferred by the earliest ballot that tells them
+    /// apart
+    EarliestVote,
+
+    /// A draw seeded by the hash of the last block in the chain
+    Lot,
+
+    /// A signed casting vote from `casting_voter`
+    CastingVote,
 }
 
-pub fn load_schema() -> Result<Schema, Box<dyn Error>> {
+/// Point scheme for the Borda count.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BordaPoints {
+    /// With n options, first place earns n-1 points down to 0 for last
+    #[default]
+    Standard,
+
+    /// Place k earns 1/k points
+    Dowdall,
+}
+
+impl Schema {
+    /// Score range in effect, `[0, 5]` unless the schema says otherwise.
+    pub fn score_range(&self) -> (i32, i32) {
+        self.score_range.unwrap_or((0, 5))
+    }
+
+    /// Credit budget in effect, 100 unless the schema says otherwise.
+    pub fn credits(&self) -> u32 {
+        self.credits.unwrap_or(100)
+    }
+
+    /// Grade scale in effect, worst first.
+    pub fn grades(&self) -> Vec<String> {
+        match &self.grades {
+            Some(grades) => grades.clone(),
+            None => ["Reject", "Poor", "Acceptable", "Good", "Very Good", "Excellent"]
+                .iter()
+                .map(|g| g.to_string())
+                .collect(),
+        }
+    }
+
+    /// Options a ballot can be counted for: `allowed` without the abstain
+    /// and blank options.
+    pub fn candidates(&self) -> Vec<String> {
+        self.allowed
+            .iter()
+            .filter(|o| Some(*o) != self.abstain.as_ref() && Some(*o) != self.blank.as_ref())
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `option` is the abstain or blank option, which a ballot must
+    /// name on its own.
+    pub fn is_set_aside(&self, option: &str) -> bool {
+        self.abstain.as_deref() == Some(option) || self.blank.as_deref() == Some(option)
+    }
+
+    /// Whether `ballot` abstains or is blank rather than counting for an
+    /// option.
+    pub fn sets_aside(&self, ballot: &Ballot) -> bool {
+        ballot.sole_option().is_some_and(|o| self.is_set_aside(o))
+    }
+
+    /// Weight `voter`'s ballot counts with, or `None` when the vote is
+    /// unweighted.
+    pub fn weight_of(&self, voter: &str) -> Option<u64> {
+        let ledger = match &self.weights {
+            None => return None,
+            Some(Weights::Ledger(ledger)) => Some(ledger),
+            Some(Weights::File { .. }) => self.weight_ledger.as_ref(),
+        };
+        let listed = ledger.and_then(|l| l.get(voter).copied());
+        Some(listed.unwrap_or(self.default_weight.unwrap_or(1)))
+    }
+
+    /// Whether `time` falls inside the voting window.
+    pub fn is_open_at(&self, time: DateTime<Utc>) -> bool {
+        self.opens_at.is_none_or(|opens| time >= opens) && self.closes_at.is_none_or(|closes| time <= closes)
+    }
+
+    /// Why `voter` may not vote on this proposal, if they may not.
+    pub fn ineligibility(&self, voter: &str) -> Option<String> {
+        let eligibility = self.eligibility.as_ref()?;
+        let email = voter_email(voter);
+
+        if eligibility.roll_only {
+            let roll = self.voters.as_deref().unwrap_or_default();
+            if !roll.iter().any(|v| v == voter || email.is_some_and(|e| v.eq_ignore_ascii_case(e))) {
+                return Some(format!("{} is not on the voter roll", voter));
+            }
+        }
+        if let Some(domains) = &eligibility.email_domains {
+            let domain = email.and_then(|e| e.rsplit_once('@')).map(|(_, d)| d);
+            if !domain.is_some_and(|d| domains.iter().any(|allowed| allowed.eq_ignore_ascii_case(d))) {
+                return Some(format!("{} does not vote with an email at {}", voter, domains.join(", ")));
+            }
+        }
+        None
+    }
+
+    /// Why a ballot cast by `voter` at `time` does not count, if it does
+    /// not: outside the voting window or from someone ineligible.
+    pub fn refusal(&self, voter: &str, time: DateTime<Utc>) -> Option<(&'static str, String)> {
+        if !self.is_open_at(time) {
+            return Some(("outside-window", format!("Cast at {} outside the voting window", time.to_rfc3339())));
+        }
+        self.ineligibility(voter).map(|message| ("ineligible-voter", message))
+    }
+
+    /// Label voters see for `option`: its `options` label, else its name.
+    pub fn label<'a>(&'a self, option: &'a str) -> &'a str {
+        self.options
+            .iter()
+            .flatten()
+            .find(|o| o.name == option)
+            .and_then(|o| o.label.as_deref())
+            .unwrap_or(option)
+    }
+
+    /// The question with this id, in a multi-question proposal.
+    pub fn question(&self, id: &str) -> Option<&Question> {
+        self.questions.as_ref()?.iter().find(|q| q.id == id)
+    }
+
+    /// What `question` is decided by: its own options, method and decision
+    /// rules, with the voter roll, weights, revote policy and voting window
+    /// of the whole proposal. A question without a quorum takes the
+    /// proposal's.
+    pub fn for_question(&self, question: &Question) -> Schema {
+        Schema {
+            questions: None,
+            weights: self.weights.clone(),
+            default_weight: self.default_weight,
+            weight_ledger: self.weight_ledger.clone(),
+            voters: self.voters.clone(),
+            eligibility: self.eligibility.clone(),
+            quorum: question.rules.quorum.or(self.quorum),
+            revote: self.revote,
+            opens_at: self.opens_at,
+            closes_at: self.closes_at,
+            public_tally: self.public_tally,
+            ..question.rules.clone()
+        }
+    }
+
+    /// Seats to fill, 1 unless the schema says otherwise.
+    pub fn seats(&self) -> usize {
+        self.seats.unwrap_or(1)
+    }
+}
+
+/// The election parameters as `cast`, `validate` and `tally` show them:
+/// metadata, method, options, voting window and eligibility.
+pub struct Summary<'a>(pub &'a Schema);
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let schema = self.0;
+        match (&schema.proposal_id, &schema.title) {
+            (Some(id), Some(title)) => writeln!(f, "Proposal {}: {}", id, title)?,
+            (Some(id), None) => writeln!(f, "Proposal {}", id)?,
+            (None, Some(title)) => writeln!(f, "Proposal: {}", title)?,
+            (None, None) => {}
+        }
+        if let Some(description) = &schema.description {
+            writeln!(f, "  {}", description)?;
+        }
+        if let Some(version) = schema.version {
+            writeln!(f, "Schema version: {}", version)?;
+        }
+
+        match &schema.questions {
+            None => {
+                writeln!(f, "Method: {}", schema.method.name())?;
+                write_options(f, schema, "")?;
+            }
+            Some(questions) => {
+                writeln!(f, "Questions:")?;
+                for question in questions {
+                    let text = question.text.as_deref().map(|t| format!(" {}", t)).unwrap_or_default();
+                    writeln!(f, "  {}:{} ({})", question.id, text, question.rules.method.name())?;
+                    write_options(f, &question.rules, "  ")?;
+                }
+            }
+        }
+
+        match (schema.opens_at, schema.closes_at) {
+            (None, None) => {}
+            (Some(opens), None) => writeln!(f, "Voting window: opens {}", opens.to_rfc3339())?,
+            (None, Some(closes)) => writeln!(f, "Voting window: closes {}", closes.to_rfc3339())?,
+            (Some(opens), Some(closes)) => {
+                writeln!(f, "Voting window: {} to {}", opens.to_rfc3339(), closes.to_rfc3339())?
+            }
+        }
+
+        if let Some(eligibility) = &schema.eligibility {
+            let mut rules = vec![];
+            if eligibility.roll_only {
+                rules.push("voters on the roll".to_string());
+            }
+            if let Some(domains) = &eligibility.email_domains {
+                rules.push(format!("emails at {}", domains.join(", ")));
+            }
+            if !rules.is_empty() {
+                writeln!(f, "Eligible: {}", rules.join("; "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes `schema`'s options on one line, or one per line when any has a
+/// label or description.
+fn write_options(f: &mut fmt::Formatter<'_>, schema: &Schema, indent: &str) -> fmt::Result {
+    let described = schema.options.iter().flatten().any(|o| o.label.is_some() || o.description.is_some());
+    if !described {
+        return writeln!(f, "{}Options: {}", indent, schema.allowed.join(", "));
+    }
+
+    writeln!(f, "{}Options:", indent)?;
+    for option in schema.options.iter().flatten() {
+        write!(f, "{}  {}", indent, option.name)?;
+        if let Some(label) = &option.label {
+            write!(f, ": {}", label)?;
+        }
+        if let Some(description) = &option.description {
+            write!(f, " ({})", description)?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+pub fn load_schema() -> Result<Schema> {
     let path = Path::new("schema.json");
 
     if !path.exists() {
-        return Err("schema.json not found in proposal branch.".into());
+        return Err(GitVoteError::Schema("schema.json not found in proposal branch.".into()));
     }
 
     let content = fs::read_to_string(path)?;
-    let schema: Schema = serde_json::from_str(&content)?;
+    let mut schema: Schema = serde_json::from_str(&content)
+        .map_err(|e| GitVoteError::Schema(format!("schema.json is malformed: {}", e)))?;
+
+    if let Some(version) = schema.version
+        && version > SCHEMA_VERSION
+    {
+        return Err(GitVoteError::Schema(format!(
+            "schema.json is version {}; this gitvote reads up to version {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    fill_allowed(&mut schema)?;
+    for question in schema.questions.iter_mut().flatten() {
+        fill_allowed(&mut question.rules)
+            .map_err(|e| GitVoteError::Schema(format!("Question '{}': {}", question.id, e)))?;
+    }
+
+    if schema.eligibility.as_ref().is_some_and(|e| e.roll_only) && schema.voters.is_none() {
+        return Err(GitVoteError::Schema("\"roll_only\" eligibility needs a voter roll in \"voters\"".into()));
+    }
+
+    if let Some(Weights::File { file, sha256 }) = &schema.weights {
+        schema.weight_ledger = Some(load_weights(file, sha256.as_deref())?);
+    }
+    check_weights(&schema)?;
+
+    match &schema.questions {
+        None if schema.allowed.is_empty() => {
+            return Err(GitVoteError::Schema("schema.json needs \"allowed\" options or \"questions\"".into()));
+        }
+        None => check_rules(&schema)?,
+        Some(questions) => {
+            if questions.is_empty() || !schema.allowed.is_empty() {
+                return Err(GitVoteError::Schema(
+                    "A proposal with \"questions\" lists its options per question, not in \"allowed\"".into(),
+                ));
+            }
+            for (i, question) in questions.iter().enumerate() {
+                if questions[..i].iter().any(|q| q.id == question.id) {
+                    return Err(GitVoteError::Schema(format!("Question '{}' is listed twice", question.id)));
+                }
+                if question.rules.questions.is_some() || question.rules.allowed.is_empty() {
+                    return Err(GitVoteError::Schema(format!(
+                        "Question '{}' needs its own \"allowed\" options and no nested questions",
+                        question.id
+                    )));
+                }
+                check_rules(&schema.for_question(question)).map_err(|e| match e {
+                    GitVoteError::Schema(message) => {
+                        GitVoteError::Schema(format!("Question '{}': {}", question.id, message))
+                    }
+                    e => e,
+                })?;
+            }
+        }
+    }
 
     Ok(schema)
 }
+
+/// Takes `allowed` from the names in `options`, or checks the two agree
+/// when both are given.
+fn fill_allowed(schema: &mut Schema) -> Result<()> {
+    let Some(options) = &schema.options else {
+        return Ok(());
+    };
+    let names: Vec<String> = options.iter().map(|o| o.name.clone()).collect();
+    if schema.allowed.is_empty() {
+        schema.allowed = names;
+    } else if schema.allowed != names {
+        return Err(GitVoteError::Schema("\"allowed\" and \"options\" list different options".into()));
+    }
+    Ok(())
+}
+
+/// The email address in a voter ID of the form `Name <email>`, or the ID
+/// itself if it is a bare address.
+fn voter_email(voter: &str) -> Option<&str> {
+    match voter.rsplit_once('<') {
+        Some((_, rest)) => rest.strip_suffix('>'),
+        None => voter.contains('@').then_some(voter),
+    }
+}
+
+/// Checks that options, decision rules and method of a single question
+/// fit together.
+fn check_rules(schema: &Schema) -> Result<()> {
+    for (kind, option) in [("abstain", &schema.abstain), ("blank", &schema.blank), ("nota", &schema.nota)] {
+        if let Some(option) = option
+            && !schema.allowed.contains(option)
+        {
+            return Err(GitVoteError::Schema(format!("{} option '{}' is not in allowed", kind, option)));
+        }
+    }
+
+    let special = [("abstain", &schema.abstain), ("blank", &schema.blank), ("nota", &schema.nota)];
+    for (i, (kind, option)) in special.iter().enumerate() {
+        if let Some(option) = option
+            && let Some((other, _)) = special[..i].iter().find(|(_, o)| o.as_ref() == Some(option))
+        {
+            return Err(GitVoteError::Schema(format!("'{}' cannot be both the {} and the {} option", option, other, kind)));
+        }
+    }
+
+    if matches!(schema.quorum, Some(Quorum::Fraction(_))) && schema.voters.is_none() {
+        return Err(GitVoteError::Schema("A fractional quorum needs a voter roll in \"voters\"".into()));
+    }
+    if let Some(Quorum::Fraction(fraction)) = schema.quorum
+        && !(fraction > 0.0 && fraction <= 1.0)
+    {
+        return Err(GitVoteError::Schema(format!(
+            "A fractional quorum must be above 0 and at most 1, not {}",
+            fraction
+        )));
+    }
+
+    if let (Some(min), Some(max)) = (schema.min_approvals, schema.max_approvals)
+        && min > max
+    {
+        return Err(GitVoteError::Schema(format!(
+            "\"min_approvals\" ({}) is above \"max_approvals\" ({})",
+            min, max
+        )));
+    }
+
+    if let Some((min, max)) = schema.score_range
+        && min > max
+    {
+        return Err(GitVoteError::Schema(format!("\"score_range\" [{}, {}] has its minimum above its maximum", min, max)));
+    }
+
+    if schema.grades.as_ref().is_some_and(|grades| grades.is_empty()) {
+        return Err(GitVoteError::Schema("\"grades\" needs at least one grade".into()));
+    }
+
+    if schema.seats == Some(0) {
+        return Err(GitVoteError::Schema("\"seats\" must be at least 1".into()));
+    }
+    // Write-ins may add candidates once votes are in
+    if !schema.write_ins && schema.seats() > schema.candidates().len() {
+        return Err(GitVoteError::Schema(format!(
+            "{} seats cannot be filled from {} candidates",
+            schema.seats(),
+            schema.candidates().len()
+        )));
+    }
+
+    if schema.tie_break == Some(TieBreakPolicy::CastingVote) && schema.casting_voter.is_none() {
+        return Err(GitVoteError::Schema("The casting_vote tie break needs a \"casting_voter\"".into()));
+    }
+
+    let lists_options = matches!(
+        schema.method,
+        Method::Plurality | Method::Irv | Method::Approval | Method::Schulze | Method::Borda | Method::Stv
+    );
+    if schema.write_ins && !lists_options {
+        return Err(GitVoteError::Schema(format!("{} ballots cannot take write-ins", schema.method.name())));
+    }
+
+    if methods::lookup(schema.method.name()).is_none() {
+        return Err(GitVoteError::Schema(format!(
+            "Unknown voting method '{}'. Available: {}",
+            schema.method.name(),
+            methods::names().join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that no weight, listed or default, exceeds [`MAX_WEIGHT`].
+fn check_weights(schema: &Schema) -> Result<()> {
+    let ledger = match &schema.weights {
+        Some(Weights::Ledger(ledger)) => Some(ledger),
+        _ => schema.weight_ledger.as_ref(),
+    };
+    let listed = ledger.into_iter().flatten().map(|(voter, weight)| (voter.as_str(), *weight));
+    let default = schema.default_weight.map(|weight| ("default_weight", weight));
+    if let Some((whose, weight)) = listed.chain(default).find(|(_, weight)| *weight > MAX_WEIGHT) {
+        return Err(GitVoteError::Schema(format!(
+            "Weight {} for {} is above the maximum of {}",
+            weight, whose, MAX_WEIGHT
+        )));
+    }
+    Ok(())
+}
+
+/// Reads a weights file, checking it against the pinned hash if there is one.
+fn load_weights(file: &Path, sha256: Option<&str>) -> Result<BTreeMap<String, u64>> {
+    let content = fs::read(file)
+        .map_err(|e| GitVoteError::Schema(format!("Cannot read weights file {:?}: {}", file, e)))?;
+
+    if let Some(expected) = sha256 {
+        let actual = format!("{:x}", Sha256::digest(&content));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(GitVoteError::Schema(format!(
+                "Weights file {:?} changed since the vote opened (sha256 {}, expected {})",
+                file, actual, expected
+            )));
+        }
+    }
+
+    serde_json::from_slice(&content)
+        .map_err(|e| GitVoteError::Schema(format!("Weights file {:?} is malformed: {}", file, e)))
+}
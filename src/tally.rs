@@ -1,44 +1,184 @@
 // src/tally.rs
 
-use std::collections::HashMap;
-use std::error::Error;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
 use std::fs;
-use gitvote::block::Block;
+use crate::block::Block;
+use crate::delegation::{self, DelegationReport};
+use crate::error::{GitVoteError, Result};
+use crate::methods::{self, Outcome};
+use crate::question::{self, Part};
+use crate::schema::{load_schema, Schema, Summary};
+use crate::tiebreak;
+use crate::verdict;
+use crate::writein::WriteIns;
 
-pub fn tally_votes() -> Result<(), Box<dyn Error>> {
-    let mut entries: Vec<_> = fs::read_dir("blocks")?
+/// What a tally counts, and what it sets aside on the way.
+pub struct Count {
+    /// One block per voter who has a vote that counts, before delegation.
+    pub cast: Vec<Block>,
+    /// The same blocks carrying any weight delegated to their voter.
+    pub counted: Vec<Block>,
+    pub delegated: Option<DelegationReport>,
+    /// Blocks replaced under the revote policy, with the block counted
+    /// instead.
+    pub superseded: Vec<(Block, usize)>,
+    /// Withdrawn votes, each with its withdrawal record.
+    pub withdrawn: Vec<(Block, Block)>,
+    /// Hash of the last block, which seeds a tie-breaking lot.
+    pub head: Option<String>,
+}
+
+/// Sorts `chain`, in block order, into what counts, with the delegations it
+/// records applied. Fails like `gitvote validate` on a vote or delegation
+/// outside the voting window, a vote from an ineligible voter, or one
+/// repeating a vote the revote policy does not allow.
+pub fn count(schema: &Schema, chain: Vec<Block>) -> Result<Count> {
+    let mut cast = vec![];
+    let mut voters: HashSet<String> = HashSet::new();
+    let mut superseded = vec![];
+    let mut withdrawn = vec![];
+    let mut delegations = vec![];
+
+    // Later blocks record what happened to earlier ones
+    let head = chain.last().map(|b| b.hash.clone());
+    let mut withdrawals: HashMap<usize, Block> =
+        chain.iter().filter_map(|b| Some((b.withdraws?, b.clone()))).collect();
+    let replaced: HashMap<usize, usize> = chain.iter().filter_map(|b| Some((b.supersedes?, b.index))).collect();
+
+    for block in chain {
+        if let Some(delegation) = delegation::recorded(&block) {
+            if let Some(message) = delegation::window_problem(schema, &delegation, block.cast_at()) {
+                return Err(GitVoteError::Validation(format!("Block {}: {}", block.index, message)));
+            }
+            delegations.push(delegation);
+            continue;
+        }
+        if block.withdraws.is_some() {
+            continue;
+        }
+        if let Some(record) = withdrawals.remove(&block.index) {
+            withdrawn.push((block, record));
+            continue;
+        }
+        if let Some(index) = block.superseded_by.or_else(|| replaced.get(&block.index).copied()) {
+            superseded.push((block, index));
+            continue;
+        }
+        if let Some((_, message)) = schema.refusal(&block.voter, block.cast_at()) {
+            return Err(GitVoteError::Validation(format!("Block {}: {}", block.index, message)));
+        }
+        if !voters.insert(block.voter.clone()) {
+            return Err(GitVoteError::Validation(format!(
+                "Duplicate vote by voter: {}; this proposal does not allow revoting",
+                block.voter
+            )));
+        }
+
+        cast.push(block);
+    }
+
+    let (counted, delegated) = match delegation::apply(schema, &cast, &delegations) {
+        Some((counted, report)) => (counted, Some(report)),
+        None => (cast.clone(), None),
+    };
+
+    Ok(Count { cast, counted, delegated, superseded, withdrawn, head })
+}
+
+/// Counts every question of the proposal, returning each with its outcome
+/// (tie broken, if the schema says how) and the result as `tally` prints
+/// it.
+pub fn outcomes<'a>(schema: &'a Schema, count: &Count) -> Result<Vec<(Part<'a>, Outcome, String)>> {
+    let mut results = vec![];
+    for part in question::split(schema, &count.counted) {
+        let mut text = String::new();
+        if let Some(question) = part.question {
+            let _ = writeln!(text, "Question {}: {}", question.id, question.text.as_deref().unwrap_or(""));
+        }
+        let mut outcome = methods::tally(&part.rules, &part.blocks)?;
+        text.push_str(&outcome.to_string());
+        let id = part.question.map(|q| q.id.as_str());
+        if let Some(tie_break) = tiebreak::apply(&part.rules, id, &part.blocks, count.head.as_deref(), &mut outcome)? {
+            text.push_str(&tie_break.to_string());
+        }
+        if part.rules.write_ins {
+            text.push_str(&WriteIns(&part.write_ins).to_string());
+        }
+        results.push((part, outcome, text));
+    }
+    Ok(results)
+}
+
+pub fn tally_votes() -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir("blocks")
+        .map_err(|_| GitVoteError::Chain("No blocks/ directory; run `gitvote build-chain` first.".into()))?
         .filter_map(|e| e.ok())
         .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("json"))
         .collect();
 
     entries.sort_by_key(|e| e.path().to_path_buf());
 
-    let mut tally: HashMap<String, u64> = HashMap::new();
-    let mut voters: HashMap<String, String> = HashMap::new();
-
+    let schema = load_schema()?;
+    let mut chain = vec![];
     for entry in &entries {
         let path = entry.path();
         let content = fs::read_to_string(&path)?;
-        let block: Block = serde_json::from_str(&content)?;
-
-        if voters.contains_key(&block.voter) {
-            continue;
-        }
-
-        *tally.entry(block.choice.clone()).or_insert(0) += 1;
-        voters.insert(block.voter.clone(), block.choice.clone());
+        let block: Block = serde_json::from_str(&content)
+            .map_err(|e| GitVoteError::Chain(format!("Malformed block {:?}: {}", path, e)))?;
+        chain.push(block);
     }
+    let count = count(&schema, chain)?;
 
     println!("✔ Tally complete:");
     println!();
+    print!("{}", Summary(&schema));
+    println!();
+    let results = outcomes(&schema, &count)?;
+    for (_, _, text) in &results {
+        print!("{}", text);
+        println!();
+    }
 
-    for (choice, count) in &tally {
-        println!("{} votes: {}", choice, count);
+    if let Some(report) = &count.delegated {
+        print!("{}", report);
     }
 
-    println!();
-    println!("Total unique voters: {}", voters.len());
+    let blocks = &count.cast;
+    if blocks.iter().any(|b| b.weight.is_some()) {
+        println!("Weights:");
+        for block in blocks {
+            println!("  {}: {}", block.voter, block.weight());
+        }
+        println!("Total weight: {}", blocks.iter().map(|b| b.weight()).sum::<u64>());
+    }
+    if !count.superseded.is_empty() {
+        println!("Superseded votes:");
+        for (block, index) in &count.superseded {
+            println!("  block {} by {} ({}), replaced by block {}", block.index, block.voter, block.ballot, index);
+        }
+    }
+    if !count.withdrawn.is_empty() {
+        println!("Withdrawn votes:");
+        for (block, record) in &count.withdrawn {
+            println!(
+                "  block {} by {} ({}), withdrawn at {} in block {}",
+                block.index,
+                block.voter,
+                block.ballot,
+                record.timestamp.to_rfc3339(),
+                record.index
+            );
+        }
+    }
+    println!("Total unique voters: {}", blocks.len());
+    for (part, outcome, _) in results {
+        println!();
+        if let Some(question) = part.question {
+            println!("Question {}:", question.id);
+        }
+        print!("{}", verdict::decide(&part.rules, &part.blocks, &outcome)?);
+    }
 
     Ok(())
 }
-
@@ -0,0 +1,172 @@
+//! Breaking a tie for first place under the schema's `tie_break` policy.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use sha2::{Digest, Sha256};
+use crate::block::Block;
+use crate::error::{GitVoteError, Result};
+use crate::methods::Outcome;
+use crate::schema::{Schema, TieBreakPolicy};
+use crate::utils::generate_fake_signature;
+use crate::vote::{Ballot, Vote};
+
+/// Where the casting voter records their decision.
+pub const CASTING_VOTE_FILE: &str = "casting-vote.json";
+
+/// Where the casting voter records their decision on `question`, or on the
+/// whole proposal if it has a single question.
+pub fn casting_vote_file(question: Option<&str>) -> String {
+    match question {
+        Some(id) => format!("casting-vote-{}.json", id),
+        None => CASTING_VOTE_FILE.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TieBreak {
+    pub policy: TieBreakPolicy,
+    pub tied: Vec<String>,
+    /// `None` if the policy could not decide yet.
+    pub winner: Option<String>,
+    /// How the winner was picked, or why none was.
+    pub detail: String,
+}
+
+/// Narrows a tie for a single seat down to one winner using the schema's
+/// policy. `schema` holds the rules of `question`, if the proposal has
+/// several, and `head` is the hash of the last block in the chain. Returns
+/// `None` when there is no tie or no policy.
+pub fn apply(
+    schema: &Schema,
+    question: Option<&str>,
+    blocks: &[Block],
+    head: Option<&str>,
+    outcome: &mut Outcome,
+) -> Result<Option<TieBreak>> {
+    let Some(policy) = schema.tie_break else {
+        return Ok(None);
+    };
+    if schema.seats() != 1 || outcome.winners.len() < 2 {
+        return Ok(None);
+    }
+
+    let tied = outcome.winners.clone();
+    let (winner, detail) = match policy {
+        TieBreakPolicy::EarliestVote => earliest_vote(schema, blocks, &tied),
+        TieBreakPolicy::Lot => lot(head, &tied),
+        TieBreakPolicy::CastingVote => casting_vote(schema, &casting_vote_file(question), &tied)?,
+    };
+
+    if let Some(winner) = &winner {
+        outcome.winners = vec![winner.clone()];
+    }
+    Ok(Some(TieBreak { policy, tied, winner, detail }))
+}
+
+fn earliest_vote(schema: &Schema, blocks: &[Block], tied: &[String]) -> (Option<String>, String) {
+    // Block order is commit order; the timestamp in a vote is the voter's
+    // own claim
+    let mut ordered: Vec<&Block> = blocks.iter().collect();
+    ordered.sort_by_key(|b| b.index);
+
+    for block in ordered {
+        if let Some(winner) = preferred(schema, &block.ballot, tied) {
+            let detail = format!("block {} by {}", block.index, block.voter);
+            return (Some(winner), detail);
+        }
+    }
+    (None, "no ballot prefers one of the tied options over the others".into())
+}
+
+/// The tied option `ballot` ranks strictly above every other tied option.
+fn preferred(schema: &Schema, ballot: &Ballot, tied: &[String]) -> Option<String> {
+    let value = |option: &str| -> Option<i64> {
+        match ballot {
+            Ballot::Choice(choice) => (choice == option).then_some(1),
+            Ballot::Ranking(ranking) => ranking.iter().position(|o| o == option).map(|p| -(p as i64)),
+            Ballot::Approval(approved) => approved.iter().any(|o| o == option).then_some(1),
+            Ballot::Scores(scores) => scores.get(option).map(|s| *s as i64),
+            Ballot::Allocation(votes) => votes.get(option).map(|v| *v as i64),
+            Ballot::Grades(grades) => {
+                let scale = schema.grades();
+                grades.get(option).and_then(|g| scale.iter().position(|s| s == g)).map(|p| p as i64)
+            }
+            // Ties are broken per question, on that question's answers
+            Ballot::Answers(_) => None,
+        }
+    };
+
+    let values: Vec<Option<i64>> = tied.iter().map(|o| value(o)).collect();
+    let best = values.iter().flatten().max()?;
+    let mut at_best = tied.iter().zip(&values).filter(|(_, v)| v.as_ref() == Some(best));
+    match (at_best.next(), at_best.next()) {
+        (Some((option, _)), None) => Some(option.clone()),
+        _ => None,
+    }
+}
+
+fn lot(head: Option<&str>, tied: &[String]) -> (Option<String>, String) {
+    let Some(seed) = head else {
+        return (None, "there is no chain to seed the draw".into());
+    };
+
+    let winner = tied
+        .iter()
+        .min_by_key(|option| format!("{:x}", Sha256::digest(format!("{}:{}", seed, option).as_bytes())))
+        .cloned();
+    let detail = format!("lowest SHA-256 of \"<seed>:<option>\" with seed {}", seed);
+    (winner, detail)
+}
+
+fn casting_vote(schema: &Schema, file: &str, tied: &[String]) -> Result<(Option<String>, String)> {
+    let admin = schema.casting_voter.as_deref().unwrap_or_default();
+    let path = Path::new(file);
+    if !path.exists() {
+        return Ok((None, format!("awaiting a casting vote from {} in {}", admin, file)));
+    }
+
+    let content = fs::read_to_string(path)?;
+    let vote: Vote = serde_json::from_str(&content)
+        .map_err(|e| GitVoteError::Validation(format!("{} is malformed: {}", file, e)))?;
+
+    if vote.voter != admin {
+        return Err(GitVoteError::Validation(format!(
+            "{} was cast by {}, not the casting voter {}",
+            file, vote.voter, admin
+        )));
+    }
+    if vote.signature != generate_fake_signature(&vote.voter, &vote.ballot.canonical()) {
+        return Err(GitVoteError::Validation(format!("{} has a bad signature", file)));
+    }
+    match &vote.ballot {
+        Ballot::Choice(choice) if tied.contains(choice) => {
+            Ok((Some(choice.clone()), format!("cast by {} at {}", admin, vote.timestamp.to_rfc3339())))
+        }
+        ballot => Err(GitVoteError::Validation(format!(
+            "The casting vote '{}' is not one of the tied options {:?}",
+            ballot, tied
+        ))),
+    }
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let policy = match self.policy {
+            TieBreakPolicy::EarliestVote => "earliest vote",
+            TieBreakPolicy::Lot => "lot",
+            TieBreakPolicy::CastingVote => "casting vote",
+        };
+        match &self.winner {
+            Some(winner) => writeln!(
+                f,
+                "Tie between {} broken by {}: {} ({})",
+                self.tied.join(", "),
+                policy,
+                winner,
+                self.detail
+            ),
+            None => writeln!(f, "Tie between {} not broken by {}: {}", self.tied.join(", "), policy, self.detail),
+        }
+    }
+}
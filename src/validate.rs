@@ -1,50 +1,122 @@
 use std::fs;
-use std::error::Error;
-use crate::schema::load_schema;
 use std::path::Path;
-use crate::vote::Vote;
+use crate::annotate::{self, CheckReport, Finding, OutputFormat, Scope};
+use crate::chain::{self, check_chain};
+use crate::delegation::check_delegations;
+use crate::error::Result;
+use crate::methods::check_ballot;
+use crate::schema::{load_schema, Revote, Schema, Summary};
+use crate::vote::{load_votes, vote_paths, Vote};
+use crate::withdrawal::{check_withdrawals, withdrawn};
 use crate::utils::generate_fake_signature;
 
 
-pub fn validate_votes() -> Result<(), Box<dyn Error>> {
+/// Validates votes and blocks, printing findings in the requested format to
+/// `output` (or stdout). Fails if anything was wrong.
+pub fn validate_votes(format: OutputFormat, output: Option<&Path>) -> Result<()> {
     let schema = load_schema()?;
-    let votes_path = Path::new("votes");
-
-    if !votes_path.exists() {
-        println!("No votes to validate.");
-        return Ok(());
+    if format == OutputFormat::Text {
+        print!("{}", Summary(&schema));
     }
+    let mut report = check_votes(&schema)?;
+    report.merge(check_chain()?);
 
-    let mut voters = vec![];
+    if format == OutputFormat::Text {
+        eprint!("{}", annotate::render(format, &report));
+    } else {
+        let rendered = annotate::render(format, &report);
+        match output {
+            Some(path) => fs::write(path, rendered)?,
+            None => print!("{}", rendered),
+        }
+    }
 
-    for entry in fs::read_dir(votes_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
+    // Keep stdout clean for machine-readable formats
+    if report.is_clean() && format == OutputFormat::Text {
+        if report.checked.is_empty() {
+            println!("No votes to validate.");
+        } else {
+            println!("✔ All votes are valid.");
         }
+    }
+
+    report.into_result()
+}
+
+/// Checks every vote file against the schema, recording every problem
+/// instead of stopping at the first one.
+pub fn check_votes(schema: &Schema) -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+    let paths = vote_paths()?;
+    let votes = load_votes()?;
+    let withdrawn = withdrawn(schema, &votes)?;
+    let built = chain::built()?;
+
+    let mut voters = vec![];
 
+    for path in paths {
         let content = fs::read_to_string(&path)?;
-        let vote: Vote = serde_json::from_str(&content)?;
+        report.checked.push(path.clone());
 
-        if !schema.allowed.contains(&vote.choice) {
-            return Err(format!("Invalid choice '{}' in {:?}", vote.choice, path).into());
-        }
+        let finding = |rule, key: &str, message| Finding {
+            file: path.clone(),
+            line: annotate::line_of(&content, key),
+            rule,
+            scope: Scope::Vote,
+            message,
+        };
+
+        let vote: Vote = match serde_json::from_str(&content) {
+            Ok(vote) => vote,
+            Err(e) => {
+                report.findings.push(Finding {
+                    line: Some(e.line()),
+                    ..finding("malformed-vote", "", format!("Malformed vote file: {}", e))
+                });
+                continue;
+            }
+        };
 
-        if voters.contains(&vote.voter) {
-            return Err(format!("Duplicate vote by voter: {}", vote.voter).into());
+        if let Err(e) = check_ballot(schema, &vote.ballot) {
+            report.findings.push(finding(e.rule, vote.ballot.key(), e.message));
         }
 
         // Validate signature
-        let expected_sig = generate_fake_signature(&vote.voter, &vote.choice);
+        let expected_sig = generate_fake_signature(&vote.voter, &vote.ballot.canonical());
 
         if vote.signature != expected_sig {
-            return Err(format!("Signature mismatch for voter {}", vote.voter).into());
+            report.findings.push(finding(
+                "bad-signature",
+                "signature",
+                format!("Signature mismatch for voter {}", vote.voter),
+            ));
+        }
+
+        // The window goes by when the chain recorded the vote, or now if
+        // it has not yet
+        let recorded_at = chain::recorded_at(&built, &vote.voter, &vote.signature, vote.timestamp);
+        if let Some((rule, message)) = schema.refusal(&vote.voter, recorded_at) {
+            let key = if rule == "outside-window" { "timestamp" } else { "voter" };
+            report.findings.push(finding(rule, key, message));
+        }
+
+        // A withdrawn vote no longer counts, so the voter may vote again
+        if withdrawn.contains_key(&path) {
+            continue;
+        }
+
+        if schema.revote == Revote::Reject && voters.contains(&vote.voter) {
+            report.findings.push(finding(
+                "duplicate-vote",
+                "voter",
+                format!("Duplicate vote by voter: {}", vote.voter),
+            ));
         }
 
         voters.push(vote.voter);
     }
 
-    println!("✔ All votes are valid.");
-    Ok(())
+    report.merge(check_withdrawals(schema, &votes)?);
+    report.merge(check_delegations(schema, &voters)?);
+    Ok(report)
 }
@@ -0,0 +1,211 @@
+//! Whether a proposal passed: quorum, required majority, abstentions and
+//! the other special options, applied to a method's outcome.
+
+use std::fmt;
+use crate::block::Block;
+use crate::error::{GitVoteError, Result};
+use crate::methods::Outcome;
+use crate::schema::{Abstentions, Majority, Quorum, Schema};
+use crate::vote::Ballot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Passed,
+    Failed,
+    NoQuorum,
+    /// "None of the above" won; the vote has to be held again
+    Rerun,
+}
+
+/// The winner's direct support against the votes the majority is taken
+/// over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Support {
+    pub option: String,
+    pub votes: u64,
+    pub of: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub status: Status,
+    /// Weight of every counted ballot, abstentions and blanks included.
+    pub turnout: u64,
+    /// Turnout the quorum requires, if there is one.
+    pub quorum: Option<u64>,
+    pub abstentions: u64,
+    pub blanks: u64,
+    pub majority: Majority,
+    pub support: Option<Support>,
+    /// Why the verdict is what it is, in a sentence.
+    pub reason: String,
+}
+
+/// Decides the proposal from the counted `blocks` and the method's
+/// `outcome`.
+pub fn decide(schema: &Schema, blocks: &[Block], outcome: &Outcome) -> Result<Verdict> {
+    let turnout: u64 = blocks.iter().map(|b| b.weight()).sum();
+    let abstentions = weight_casting(blocks, schema.abstain.as_deref());
+    let blanks = weight_casting(blocks, schema.blank.as_deref());
+
+    let mut verdict = Verdict {
+        status: Status::Failed,
+        turnout,
+        quorum: required_turnout(schema)?,
+        abstentions,
+        blanks,
+        majority: schema.majority,
+        support: None,
+        reason: String::new(),
+    };
+
+    if let Some(required) = verdict.quorum
+        && turnout < required
+    {
+        verdict.status = Status::NoQuorum;
+        verdict.reason = format!("turnout {} is below the quorum of {}", turnout, required);
+        return Ok(verdict);
+    }
+
+    let seats = schema.seats();
+    match outcome.winners.as_slice() {
+        [] => {
+            verdict.reason = "there is no winner".into();
+            return Ok(verdict);
+        }
+        [winner] if schema.nota.as_ref() == Some(winner) => {
+            verdict.status = Status::Rerun;
+            verdict.reason = format!("'{}' won, so the vote must be held again", winner);
+            return Ok(verdict);
+        }
+        winners if winners.len() > seats => {
+            verdict.reason = format!("tie between {}", winners.join(", "));
+            return Ok(verdict);
+        }
+        winners if winners.len() < seats => {
+            verdict.reason = format!("only {} of {} seats filled", winners.len(), seats);
+            return Ok(verdict);
+        }
+        _ if seats > 1 => {
+            verdict.status = Status::Passed;
+            verdict.reason = format!("{} seats filled; majority rules apply to single winners only", seats);
+            return Ok(verdict);
+        }
+        _ => {}
+    }
+
+    let winner = &outcome.winners[0];
+    // A ranking only records first preferences directly; ranked methods
+    // may elect someone else once later preferences are counted
+    let ranked = blocks.iter().any(|b| matches!(b.ballot, Ballot::Ranking(_)));
+    let Some(votes) = direct_support(blocks, winner).filter(|_| !ranked) else {
+        verdict.status = Status::Passed;
+        verdict.reason = format!("{} wins; {} ballots do not measure direct support, so no majority rule applies", winner, schema.method.name());
+        return Ok(verdict);
+    };
+
+    // Blank ballots never count toward the majority
+    let of = match schema.abstentions {
+        Abstentions::Exclude => turnout.saturating_sub(abstentions).saturating_sub(blanks),
+        Abstentions::Against => turnout.saturating_sub(blanks),
+    };
+    let carried = of > 0
+        && match schema.majority {
+            Majority::Simple => votes * 2 > of,
+            Majority::TwoThirds => votes * 3 >= of * 2,
+            Majority::Unanimous => votes == of,
+        };
+
+    verdict.status = if carried { Status::Passed } else { Status::Failed };
+    verdict.reason = format!(
+        "{} has {} of {} votes; {} {}",
+        winner,
+        votes,
+        of,
+        describe(schema.majority),
+        if carried { "reached" } else { "not reached" }
+    );
+    verdict.support = Some(Support { option: winner.clone(), votes, of });
+    Ok(verdict)
+}
+
+fn required_turnout(schema: &Schema) -> Result<Option<u64>> {
+    Ok(match schema.quorum {
+        None => None,
+        Some(Quorum::Voters(n)) => Some(n),
+        Some(Quorum::Fraction(fraction)) => {
+            let roll = schema
+                .voters
+                .as_ref()
+                .ok_or_else(|| GitVoteError::Schema("A fractional quorum needs a voter roll in \"voters\"".into()))?;
+            let roll_weight: u64 = roll.iter().map(|v| schema.weight_of(v).unwrap_or(1)).sum();
+            Some((fraction * roll_weight as f64).ceil() as u64)
+        }
+    })
+}
+
+/// Weight of ballots casting nothing but `option`.
+fn weight_casting(blocks: &[Block], option: Option<&str>) -> u64 {
+    let Some(option) = option else {
+        return 0;
+    };
+    blocks
+        .iter()
+        .filter(|b| b.ballot.sole_option() == Some(option))
+        .map(|b| b.weight())
+        .sum()
+}
+
+/// Weight of ballots choosing, ranking first or approving `option`; `None`
+/// for ballot kinds with no such notion.
+pub(crate) fn direct_support(blocks: &[Block], option: &str) -> Option<u64> {
+    let mut votes = 0;
+    for block in blocks {
+        let supports = match &block.ballot {
+            Ballot::Choice(choice) => choice == option,
+            Ballot::Ranking(ranking) => ranking.first().is_some_and(|o| o == option),
+            Ballot::Approval(approved) => approved.iter().any(|o| o == option),
+            _ => return None,
+        };
+        if supports {
+            votes += block.weight();
+        }
+    }
+    Some(votes)
+}
+
+fn describe(majority: Majority) -> &'static str {
+    match majority {
+        Majority::Simple => "simple majority (more than half)",
+        Majority::TwoThirds => "two-thirds majority",
+        Majority::Unanimous => "unanimity",
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self.status {
+            Status::Passed => "PASSED",
+            Status::Failed => "FAILED",
+            Status::NoQuorum => "NO QUORUM",
+            Status::Rerun => "RE-RUN",
+        };
+        writeln!(f, "Verdict: {}", status)?;
+
+        match self.quorum {
+            Some(required) => writeln!(f, "  Turnout: {} (quorum {})", self.turnout, required)?,
+            None => writeln!(f, "  Turnout: {} (no quorum required)", self.turnout)?,
+        }
+        if self.abstentions > 0 {
+            writeln!(f, "  Abstentions: {}", self.abstentions)?;
+        }
+        if self.blanks > 0 {
+            writeln!(f, "  Blank: {}", self.blanks)?;
+        }
+        if let Some(support) = &self.support {
+            let share = support.votes as f64 * 100.0 / support.of as f64;
+            writeln!(f, "  Support: {} {} of {} ({:.1}%)", support.option, support.votes, support.of, share)?;
+        }
+        writeln!(f, "  Reason: {}", self.reason)
+    }
+}
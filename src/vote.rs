@@ -1,4 +1,10 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
 use chrono::{ DateTime, Utc };
+use crate::error::Result;
+use crate::git::json_files_in_commit_order;
 
 use serde::{
     Deserialize,
@@ -10,9 +16,121 @@ pub struct Vote {
     /// The voter's ID (e.g. username or public key)
     pub voter: String,
 
-    /// Their selected choice (e.g. a candidate or option)
-    pub choice: String,
+    /// What they voted for; serialized as a `choice`, `ranking`, ... field
+    #[serde(flatten)]
+    pub ballot: Ballot,
 
     pub signature: String,
     pub timestamp: DateTime<Utc>,
 }
+
+/// The content of a vote. Which kind is expected depends on the proposal's
+/// voting method.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Ballot {
+    /// A single selected option (e.g. a candidate)
+    Choice(String),
+
+    /// Options in order of preference, most preferred first
+    Ranking(Vec<String>),
+
+    /// Every option the voter finds acceptable, in no particular order
+    Approval(Vec<String>),
+
+    /// A score for each option, within the schema's range
+    Scores(BTreeMap<String, i32>),
+
+    /// Votes placed on each option, paid for from the schema's credit budget
+    Allocation(BTreeMap<String, u32>),
+
+    /// A grade from the schema's scale for each option
+    Grades(BTreeMap<String, String>),
+
+    /// One ballot per question answered, by question id, for proposals
+    /// with several questions
+    Answers(BTreeMap<String, Ballot>),
+}
+
+impl Ballot {
+    /// Field name this ballot is stored under, for pointing at it in files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Ballot::Choice(_) => "choice",
+            Ballot::Ranking(_) => "ranking",
+            Ballot::Approval(_) => "approval",
+            Ballot::Scores(_) => "scores",
+            Ballot::Allocation(_) => "allocation",
+            Ballot::Grades(_) => "grades",
+            Ballot::Answers(_) => "answers",
+        }
+    }
+
+    /// The one option this ballot names, if it names exactly one: a choice,
+    /// or a ranking or approval of a single option.
+    pub fn sole_option(&self) -> Option<&str> {
+        match self {
+            Ballot::Choice(choice) => Some(choice),
+            Ballot::Ranking(options) | Ballot::Approval(options) if options.len() == 1 => Some(&options[0]),
+            _ => None,
+        }
+    }
+
+    /// Stable text form that signatures are computed over. A plain choice
+    /// signs as itself so existing votes keep verifying.
+    pub fn canonical(&self) -> String {
+        match self {
+            Ballot::Choice(choice) => choice.clone(),
+            Ballot::Ranking(ranking) => format!("rank:{}", ranking.join(">")),
+            Ballot::Approval(approved) => format!("approve:{}", approved.join(",")),
+            Ballot::Scores(scores) => format!("score:{}", join_pairs(scores, "=", ",")),
+            Ballot::Allocation(votes) => format!("allocate:{}", join_pairs(votes, "=", ",")),
+            Ballot::Grades(grades) => format!("grade:{}", join_pairs(grades, "=", ",")),
+            Ballot::Answers(answers) => format!(
+                "answers:{}",
+                answers.iter().map(|(q, b)| format!("{}={}", q, b.canonical())).collect::<Vec<_>>().join(";")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Ballot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ballot::Choice(choice) => write!(f, "{}", choice),
+            Ballot::Ranking(ranking) => write!(f, "{}", ranking.join(" > ")),
+            Ballot::Approval(approved) => write!(f, "approve {}", approved.join(", ")),
+            Ballot::Scores(scores) => write!(f, "score {}", join_pairs(scores, "=", ", ")),
+            Ballot::Allocation(votes) => write!(f, "allocate {}", join_pairs(votes, "=", ", ")),
+            Ballot::Grades(grades) => write!(f, "grade {}", join_pairs(grades, "=", ", ")),
+            Ballot::Answers(answers) => write!(f, "{}", join_pairs(answers, ": ", "; ")),
+        }
+    }
+}
+
+fn join_pairs<V: fmt::Display>(map: &BTreeMap<String, V>, sep: &str, delim: &str) -> String {
+    map.iter()
+        .map(|(k, v)| format!("{}{}{}", k, sep, v))
+        .collect::<Vec<_>>()
+        .join(delim)
+}
+
+/// Every readable vote file with its vote, in the order they were cast.
+/// Malformed files are skipped; validation reports them.
+pub fn load_votes() -> Result<Vec<(PathBuf, Vote)>> {
+    let mut votes = vec![];
+    for path in vote_paths()? {
+        let content = fs::read_to_string(&path)?;
+        if let Ok(vote) = serde_json::from_str(&content) {
+            votes.push((path, vote));
+        }
+    }
+    Ok(votes)
+}
+
+/// Vote files in the order they were cast: by the commit that added them,
+/// then any not yet committed (or outside a git repo) by file name. Empty
+/// if `votes/` is missing.
+pub fn vote_paths() -> Result<Vec<PathBuf>> {
+    json_files_in_commit_order("votes")
+}
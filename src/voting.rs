@@ -1,46 +1,226 @@
 use chrono::Utc;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
 use std::fs;
-use std::process::Command;
-use std::error::Error;
 use std::path::Path;
 use uuid::Uuid;
-use crate::vote::Vote;
-use crate::git::get_git_voter;
+use crate::delegation::{Delegation, DelegationScope, DELEGATIONS_DIR};
+use crate::error::{GitVoteError, Result};
+use crate::question;
+use crate::vote::load_votes;
+use crate::withdrawal::{self, Withdrawal, WITHDRAWALS_DIR};
+use crate::methods::check_ballot;
+use crate::vote::{Ballot, Vote};
+use crate::git::{get_git_voter, run_git};
 use crate::utils::generate_fake_signature;
-use crate::schema::load_schema;
+use crate::schema::{load_schema, Summary, TieBreakPolicy};
+use crate::tiebreak::casting_vote_file;
 
 /// Casts a vote by writing a vote intent file and signing the commit.
 /// Assumes user has already checked out the correct election branch.
-pub fn cast_vote(choice: &str) -> Result<(), Box<dyn Error>> {
+pub fn cast_vote(ballot: Ballot) -> Result<()> {
     if !Path::new(".git").exists() {
-        return Err("Not inside a git repo.".into());
+        return Err(GitVoteError::Git("Not inside a git repo.".into()));
     }
 
     let schema = load_schema()?;
-    if !schema.allowed.contains(&choice.to_string()) {
-        return Err(format!("Invalid choice '{}'. Allowed: {:?}", choice, schema.allowed).into());
+    if let Err(e) = check_ballot(&schema, &ballot) {
+        return Err(GitVoteError::Validation(e.message));
     }
 
     let voter = get_git_voter()?;
-    let signature = generate_fake_signature(&voter, choice);
+    let timestamp = Utc::now();
+    if let Some((_, message)) = schema.refusal(&voter, timestamp) {
+        return Err(GitVoteError::Validation(message));
+    }
+    let signature = generate_fake_signature(&voter, &ballot.canonical());
 
     fs::create_dir_all("votes")?;
     let filename = format!("votes/vote-{}.json", Uuid::new_v4());
 
-    let timestamp = Utc::now();
-
+    let message = format!("vote: {}", ballot);
     let vote = Vote {
         voter,
-        choice: choice.to_string(),
+        ballot,
         signature,
         timestamp,
     };
-    let json = serde_json::to_string_pretty(&vote)?;
+    let json = serde_json::to_string_pretty(&vote).expect("vote serializes");
     fs::write(&filename, json)?;
 
-    Command::new("git").args(["add", &filename]).status()?;
-    Command::new("git").args(["commit", "-m", &format!("vote: {}", choice)]).status()?;
+    run_git(&["add", &filename])?;
+    run_git(&["commit", "-m", &message])?;
 
+    print!("{}", Summary(&schema));
     println!("✔ Vote recorded as {}", filename);
     Ok(())
 }
+
+/// Casts answers to a multi-question proposal given as `question=answer`.
+pub fn cast_answers(answers: Vec<(String, String)>) -> Result<()> {
+    let schema = load_schema()?;
+    if schema.questions.is_none() {
+        return Err(GitVoteError::Usage("This proposal has a single question; --answer is for multi-question proposals.".into()));
+    }
+
+    let mut ballots = BTreeMap::new();
+    for (id, text) in answers {
+        let question = schema
+            .question(&id)
+            .ok_or_else(|| GitVoteError::Validation(format!("Unknown question '{}'", id)))?;
+        ballots.insert(id, question::parse_answer(&schema.for_question(question), &text)?);
+    }
+    cast_vote(Ballot::Answers(ballots))
+}
+
+/// Asks each question of a multi-question proposal in turn and casts the
+/// answers.
+pub fn cast_interactive(input: impl BufRead, output: impl Write) -> Result<()> {
+    let schema = load_schema()?;
+    if schema.questions.is_none() {
+        return Err(GitVoteError::Usage(
+            "Give your ballot with one of --choice, --rank, --approve, --score, --allocate or --grade.".into(),
+        ));
+    }
+
+    let answers = question::ask(&schema, input, output)?;
+    cast_vote(Ballot::Answers(answers))
+}
+
+/// Records the casting voter's decision for breaking a tie, on `question`
+/// in a proposal with several, as a signed `casting-vote.json` (or
+/// `casting-vote-<question>.json`), and commits it.
+pub fn cast_casting_vote(choice: String, question: Option<String>) -> Result<()> {
+    if !Path::new(".git").exists() {
+        return Err(GitVoteError::Git("Not inside a git repo.".into()));
+    }
+
+    let proposal = load_schema()?;
+    let schema = match (&proposal.questions, &question) {
+        (None, None) => proposal.clone(),
+        (Some(_), Some(id)) => match proposal.question(id) {
+            Some(q) => proposal.for_question(q),
+            None => return Err(GitVoteError::Usage(format!("This proposal has no question '{}'.", id))),
+        },
+        (Some(_), None) => {
+            return Err(GitVoteError::Usage("This proposal has several questions; name one with --question.".into()));
+        }
+        (None, Some(_)) => return Err(GitVoteError::Usage("This proposal has a single question.".into())),
+    };
+    if schema.tie_break != Some(TieBreakPolicy::CastingVote) {
+        return Err(GitVoteError::Usage("This proposal does not break ties with a casting vote.".into()));
+    }
+
+    let voter = get_git_voter()?;
+    if schema.casting_voter.as_deref() != Some(voter.as_str()) {
+        return Err(GitVoteError::Validation(format!("{} is not the casting voter for this proposal", voter)));
+    }
+    if !schema.candidates().contains(&choice) {
+        return Err(GitVoteError::Validation(format!(
+            "Invalid choice '{}'. Allowed: {:?}",
+            choice,
+            schema.candidates()
+        )));
+    }
+
+    let ballot = Ballot::Choice(choice);
+    let message = format!("casting vote: {}", ballot);
+    let vote = Vote {
+        signature: generate_fake_signature(&voter, &ballot.canonical()),
+        voter,
+        ballot,
+        timestamp: Utc::now(),
+    };
+    let json = serde_json::to_string_pretty(&vote).expect("vote serializes");
+    let file = casting_vote_file(question.as_deref());
+    fs::write(&file, json)?;
+
+    run_git(&["add", &file])?;
+    run_git(&["commit", "-m", &message])?;
+
+    println!("✔ Casting vote recorded in {}", file);
+    Ok(())
+}
+
+/// Delegates the current voter's vote to `delegate`, for this proposal or
+/// with `scope` global for every proposal, and commits the signed file.
+pub fn delegate_vote(delegate: String, scope: DelegationScope) -> Result<()> {
+    if !Path::new(".git").exists() {
+        return Err(GitVoteError::Git("Not inside a git repo.".into()));
+    }
+
+    let delegator = get_git_voter()?;
+    if delegator == delegate {
+        return Err(GitVoteError::Validation("You cannot delegate to yourself.".into()));
+    }
+    if scope == DelegationScope::Proposal
+        && let Some(message) = load_schema()?.ineligibility(&delegator)
+    {
+        return Err(GitVoteError::Validation(message));
+    }
+
+    let mut delegation = Delegation {
+        delegator,
+        delegate,
+        scope,
+        signature: String::new(),
+        timestamp: Utc::now(),
+    };
+    delegation.signature = generate_fake_signature(&delegation.delegator, &delegation.canonical());
+
+    fs::create_dir_all(DELEGATIONS_DIR)?;
+    let filename = format!("{}/delegation-{}.json", DELEGATIONS_DIR, Uuid::new_v4());
+    let json = serde_json::to_string_pretty(&delegation).expect("delegation serializes");
+    fs::write(&filename, json)?;
+
+    run_git(&["add", &filename])?;
+    run_git(&["commit", "-m", &format!("delegate: to {}", delegation.delegate)])?;
+
+    println!("✔ Delegation recorded as {}", filename);
+    Ok(())
+}
+
+/// Withdraws every vote the current voter has cast and not yet withdrawn,
+/// writing a signed withdrawal record for each and committing them.
+pub fn withdraw_vote() -> Result<()> {
+    if !Path::new(".git").exists() {
+        return Err(GitVoteError::Git("Not inside a git repo.".into()));
+    }
+
+    let schema = load_schema()?;
+    let timestamp = Utc::now();
+    if !schema.is_open_at(timestamp) {
+        return Err(GitVoteError::Validation("Votes can only be withdrawn while voting is open.".into()));
+    }
+
+    let voter = get_git_voter()?;
+    let votes = load_votes()?;
+    let withdrawn = withdrawal::withdrawn(&schema, &votes)?;
+    let mine: Vec<_> = votes
+        .iter()
+        .filter(|(path, vote)| vote.voter == voter && !withdrawn.contains_key(path))
+        .map(|(path, _)| path.clone())
+        .collect();
+    if mine.is_empty() {
+        return Err(GitVoteError::Validation(format!("{} has no vote to withdraw", voter)));
+    }
+
+    fs::create_dir_all(WITHDRAWALS_DIR)?;
+    let mut files = vec![];
+    for vote in mine {
+        let mut record = Withdrawal { voter: voter.clone(), vote, signature: String::new(), timestamp };
+        record.signature = generate_fake_signature(&record.voter, &record.canonical());
+
+        let filename = format!("{}/withdrawal-{}.json", WITHDRAWALS_DIR, Uuid::new_v4());
+        fs::write(&filename, serde_json::to_string_pretty(&record).expect("withdrawal serializes"))?;
+        run_git(&["add", &filename])?;
+        files.push(filename);
+    }
+
+    run_git(&["commit", "-m", "withdraw: vote retracted"])?;
+
+    for filename in files {
+        println!("✔ Withdrawal recorded as {}", filename);
+    }
+    Ok(())
+}
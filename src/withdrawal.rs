@@ -0,0 +1,156 @@
+//! Retracting a vote before the voting window closes.
+//!
+//! A withdrawal is a signed file in `withdrawals/` naming the vote file it
+//! retracts. It only counts if it comes from that vote's voter and the
+//! chain recorded it inside the voting window. The withdrawn vote stays in
+//! the chain as it was, and the withdrawal is added after it as a block of its own, so the
+//! audit trail keeps both.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::annotate::{self, CheckReport, Finding, Scope};
+use crate::error::Result;
+use crate::chain;
+use crate::git::json_files_in_commit_order;
+use crate::schema::Schema;
+use crate::utils::generate_fake_signature;
+use crate::vote::Vote;
+
+pub const WITHDRAWALS_DIR: &str = "withdrawals";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Withdrawal {
+    /// Who retracts the vote; must be the voter who cast it
+    pub voter: String,
+
+    /// Path of the retracted vote file, e.g. `votes/vote-<uuid>.json`
+    pub vote: PathBuf,
+
+    pub signature: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Withdrawal {
+    /// Text the signature covers.
+    pub fn canonical(&self) -> String {
+        format!("withdraw:{}", self.vote.display())
+    }
+}
+
+/// Withdrawal files in `withdrawals/`, in the order they were committed.
+pub fn withdrawal_paths() -> Result<Vec<PathBuf>> {
+    json_files_in_commit_order(WITHDRAWALS_DIR)
+}
+
+/// Why `withdrawal`, made at `made`, does not count, as a rule, the field at
+/// fault and a message. `made` is when the chain recorded the withdrawal
+/// (see [`chain::recorded_at`]): the timestamp inside the file is the
+/// voter's own claim and is not trusted for the window.
+fn problem(
+    schema: &Schema,
+    withdrawal: &Withdrawal,
+    made: DateTime<Utc>,
+    votes: &[(PathBuf, Vote)],
+) -> Option<(&'static str, &'static str, String)> {
+    let Some((_, vote)) = votes.iter().find(|(path, _)| *path == withdrawal.vote) else {
+        return Some(("withdrawal-target", "vote", format!("No vote file {:?} to withdraw", withdrawal.vote)));
+    };
+    if vote.voter != withdrawal.voter {
+        return Some((
+            "withdrawal-voter",
+            "voter",
+            format!("Only {} can withdraw {:?}", vote.voter, withdrawal.vote),
+        ));
+    }
+    if withdrawal.signature != generate_fake_signature(&withdrawal.voter, &withdrawal.canonical()) {
+        return Some((
+            "bad-signature",
+            "signature",
+            format!("Signature mismatch for withdrawal by {}", withdrawal.voter),
+        ));
+    }
+    if !schema.is_open_at(made) {
+        return Some((
+            "withdrawal-window",
+            "timestamp",
+            format!("Withdrawn at {} outside the voting window", made.to_rfc3339()),
+        ));
+    }
+    None
+}
+
+/// Valid withdrawal files with their withdrawal, the first for each vote,
+/// in the order they were committed. Malformed and invalid withdrawals are
+/// left out; [`check_withdrawals`] reports them.
+pub fn valid_withdrawals(schema: &Schema, votes: &[(PathBuf, Vote)]) -> Result<Vec<(PathBuf, Withdrawal)>> {
+    let built = chain::built()?;
+    let mut valid: Vec<(PathBuf, Withdrawal)> = vec![];
+    for path in withdrawal_paths()? {
+        let content = fs::read_to_string(&path)?;
+        let Ok(withdrawal) = serde_json::from_str::<Withdrawal>(&content) else {
+            continue;
+        };
+        let made = chain::recorded_at(&built, &withdrawal.voter, &withdrawal.signature, withdrawal.timestamp);
+        if problem(schema, &withdrawal, made, votes).is_none() && !valid.iter().any(|(_, w)| w.vote == withdrawal.vote) {
+            valid.push((path, withdrawal));
+        }
+    }
+    Ok(valid)
+}
+
+/// Vote files validly withdrawn, with when.
+pub fn withdrawn(schema: &Schema, votes: &[(PathBuf, Vote)]) -> Result<HashMap<PathBuf, DateTime<Utc>>> {
+    Ok(valid_withdrawals(schema, votes)?
+        .into_iter()
+        .map(|(_, w)| (w.vote, w.timestamp))
+        .collect())
+}
+
+/// Checks every withdrawal file against the `votes` it may retract.
+pub fn check_withdrawals(schema: &Schema, votes: &[(PathBuf, Vote)]) -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+    let mut seen = vec![];
+    let built = chain::built()?;
+
+    for path in withdrawal_paths()? {
+        let content = fs::read_to_string(&path)?;
+        report.checked.push(path.clone());
+
+        let finding = |rule, key: &str, message| Finding {
+            file: path.clone(),
+            line: annotate::line_of(&content, key),
+            rule,
+            scope: Scope::Vote,
+            message,
+        };
+
+        let withdrawal: Withdrawal = match serde_json::from_str(&content) {
+            Ok(withdrawal) => withdrawal,
+            Err(e) => {
+                report.findings.push(Finding {
+                    line: Some(e.line()),
+                    ..finding("malformed-withdrawal", "", format!("Malformed withdrawal file: {}", e))
+                });
+                continue;
+            }
+        };
+
+        if seen.contains(&withdrawal.vote) {
+            report.findings.push(finding(
+                "duplicate-withdrawal",
+                "vote",
+                format!("{:?} is already withdrawn", withdrawal.vote),
+            ));
+        }
+        let made = chain::recorded_at(&built, &withdrawal.voter, &withdrawal.signature, withdrawal.timestamp);
+        if let Some((rule, key, message)) = problem(schema, &withdrawal, made, votes) {
+            report.findings.push(finding(rule, key, message));
+        }
+        seen.push(withdrawal.vote);
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,112 @@
+//! Write-in candidates: options voters name themselves when the schema
+//! allows it.
+//!
+//! Names are normalized before they are compared: Unicode NFC, whitespace
+//! trimmed and collapsed, and Unicode case folded, so "Alice", " alice "
+//! and "ALICE" are one candidate, as are "STRASSE" and "straße". A write-in matching a listed option after
+//! normalizing counts as that option.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use unicode_normalization::UnicodeNormalization;
+use crate::block::Block;
+use crate::schema::Schema;
+use crate::vote::Ballot;
+
+/// The form write-in names are compared and counted in.
+pub fn normalize(name: &str) -> String {
+    let spaced = name.nfc().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ");
+    caseless::default_case_fold_str(&spaced).nfc().collect()
+}
+
+/// The option `name` counts as: the listed option itself or, with
+/// write-ins allowed, the listed option or write-in it normalizes to.
+/// `None` if it is not acceptable.
+pub fn resolve(schema: &Schema, name: &str) -> Option<String> {
+    if schema.allowed.iter().any(|o| o == name) {
+        return Some(name.to_string());
+    }
+    if !schema.write_ins {
+        return None;
+    }
+
+    let key = normalize(name);
+    if key.is_empty() {
+        return None;
+    }
+    let listed = schema.allowed.iter().find(|o| normalize(o) == key);
+    Some(listed.cloned().unwrap_or(key))
+}
+
+/// One write-in candidate as counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteIn {
+    pub name: String,
+    /// Weight of the ballots naming it.
+    pub ballots: u64,
+    /// Spellings voters used, merged into `name`.
+    pub variants: BTreeSet<String>,
+}
+
+/// Rewrites every ballot to name options as [`resolve`] counts them, and
+/// adds the write-ins found to `allowed`, in order of first appearance.
+/// Returns the schema and blocks to count, and the write-ins merged.
+pub fn merge(schema: &Schema, blocks: &[Block]) -> (Schema, Vec<Block>, Vec<WriteIn>) {
+    if !schema.write_ins {
+        return (schema.clone(), blocks.to_vec(), vec![]);
+    }
+
+    let mut write_ins: Vec<WriteIn> = vec![];
+    let mut merged = vec![];
+    for block in blocks {
+        let mut named = BTreeSet::new();
+        let mut rename = |raw: &String| -> String {
+            let Some(option) = resolve(schema, raw) else {
+                return raw.clone();
+            };
+            if !schema.allowed.contains(&option) {
+                let index = match write_ins.iter().position(|w| w.name == option) {
+                    Some(index) => index,
+                    None => {
+                        write_ins.push(WriteIn { name: option.clone(), ballots: 0, variants: BTreeSet::new() });
+                        write_ins.len() - 1
+                    }
+                };
+                write_ins[index].variants.insert(raw.clone());
+                if named.insert(index) {
+                    write_ins[index].ballots += block.weight();
+                }
+            }
+            option
+        };
+
+        let ballot = match &block.ballot {
+            Ballot::Choice(choice) => Ballot::Choice(rename(choice)),
+            Ballot::Ranking(ranking) => Ballot::Ranking(ranking.iter().map(&mut rename).collect()),
+            Ballot::Approval(approved) => Ballot::Approval(approved.iter().map(&mut rename).collect()),
+            other => other.clone(),
+        };
+        merged.push(Block { ballot, ..block.clone() });
+    }
+
+    let mut counted = schema.clone();
+    counted.allowed.extend(write_ins.iter().map(|w| w.name.clone()));
+    (counted, merged, write_ins)
+}
+
+/// The write-in section of a tally.
+pub struct WriteIns<'a>(pub &'a [WriteIn]);
+
+impl fmt::Display for WriteIns<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Write-ins:")?;
+        if self.0.is_empty() {
+            return writeln!(f, "  none");
+        }
+        for w in self.0 {
+            let variants: Vec<String> = w.variants.iter().map(|v| format!("{:?}", v)).collect();
+            writeln!(f, "  {}: named on {} ballot(s), as {}", w.name, w.ballots, variants.join(", "))?;
+        }
+        Ok(())
+    }
+}
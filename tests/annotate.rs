@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use gitvote::annotate::{render, CheckReport, Finding, OutputFormat, Scope};
+
+fn sample_report() -> CheckReport {
+    CheckReport {
+        checked: vec![
+            PathBuf::from("votes/vote-a.json"),
+            PathBuf::from("votes/vote-b.json"),
+        ],
+        findings: vec![Finding {
+            file: PathBuf::from("votes/vote-b.json"),
+            line: Some(3),
+            rule: "invalid-choice",
+            scope: Scope::Vote,
+            message: "Invalid choice 'green', expected <blue|red>".to_string(),
+        }],
+    }
+}
+
+#[test]
+fn github_annotations_point_at_file_and_line() {
+    let out = render(OutputFormat::Github, &sample_report());
+
+    assert_eq!(
+        out.trim(),
+        "::error file=votes/vote-b.json,line=3,title=gitvote invalid-choice::Invalid choice 'green', expected <blue|red>"
+    );
+}
+
+#[test]
+fn gitlab_code_quality_is_a_json_array() {
+    let out = render(OutputFormat::Gitlab, &sample_report());
+    let issues: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    assert_eq!(issues[0]["check_name"], "invalid-choice");
+    assert_eq!(issues[0]["location"]["path"], "votes/vote-b.json");
+    assert_eq!(issues[0]["location"]["lines"]["begin"], 3);
+    assert_eq!(issues[0]["fingerprint"].as_str().unwrap().len(), 64);
+}
+
+#[test]
+fn junit_has_one_case_per_checked_file() {
+    let out = render(OutputFormat::Junit, &sample_report());
+
+    assert!(out.contains(r#"tests="2" failures="1""#));
+    assert!(out.contains(r#"<testcase name="votes/vote-a.json" classname="gitvote" file="votes/vote-a.json"/>"#));
+    assert!(out.contains("expected &lt;blue|red&gt;"));
+}
+
+#[test]
+fn sarif_lists_rules_and_results() {
+    let out = render(OutputFormat::Sarif, &sample_report());
+    let sarif: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    let run = &sarif["runs"][0];
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "invalid-choice");
+    assert_eq!(run["results"][0]["locations"][0]["physicalLocation"]["region"]["startLine"], 3);
+}
+
+#[test]
+fn vote_findings_take_precedence_over_chain_findings() {
+    let mut report = sample_report();
+    report.findings.push(Finding {
+        file: PathBuf::from("blocks/block-0000.json"),
+        line: None,
+        rule: "hash-mismatch",
+        scope: Scope::Chain,
+        message: "tampered".to_string(),
+    });
+
+    assert_eq!(report.into_result().unwrap_err().exit_code(), 5);
+}
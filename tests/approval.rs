@@ -0,0 +1,46 @@
+mod common;
+
+use gitvote::methods::{approval, check_ballot, tally};
+use gitvote::schema::Schema;
+use gitvote::vote::Ballot;
+use common::{blocks, options, schema_error};
+
+#[test]
+fn counts_approvals_per_option() {
+    let opts = options(&["mon", "tue", "wed"]);
+    let ballots = [options(&["mon", "tue"]), options(&["tue"]), options(&["wed", "tue"]), options(&[])];
+    let refs: Vec<(&[String], u64)> = ballots.iter().map(|b| (b.as_slice(), 1)).collect();
+
+    let result = approval::count(&opts, &refs);
+
+    assert_eq!(
+        result.counts,
+        vec![("mon".to_string(), 1), ("tue".to_string(), 3), ("wed".to_string(), 1)]
+    );
+    assert_eq!(result.winners, vec!["tue".to_string()]);
+    assert!(result.to_string().contains("tue approvals: 3 of 4"));
+}
+
+#[test]
+fn enforces_min_and_max_approvals() {
+    let schema: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b", "c"], "method": "approval", "min_approvals": 1, "max_approvals": 2 }"#,
+    ).unwrap();
+
+    assert!(check_ballot(&schema, &Ballot::Approval(options(&["a", "c"]))).is_ok());
+    assert!(check_ballot(&schema, &Ballot::Approval(options(&[]))).is_err());
+    assert!(check_ballot(&schema, &Ballot::Approval(options(&["a", "b", "c"]))).is_err());
+    assert!(check_ballot(&schema, &Ballot::Approval(options(&["a", "a"]))).is_err());
+    assert!(check_ballot(&schema, &Ballot::Approval(options(&["z"]))).is_err());
+
+    let out = schema_error(r#"{ "allowed": ["a", "b", "c"], "method": "approval", "min_approvals": 3, "max_approvals": 2 }"#);
+    assert!(out.contains("\"min_approvals\" (3) is above \"max_approvals\" (2)"), "{}", out);
+}
+
+#[test]
+fn reports_ties() {
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a", "b"], "method": "approval" }"#).unwrap();
+    let ballots = vec![Ballot::Approval(options(&["a"])), Ballot::Approval(options(&["b"]))];
+
+    assert!(tally(&schema, &blocks(&schema, ballots)).unwrap().text.contains("Tie between: a, b"));
+}
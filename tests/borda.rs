@@ -0,0 +1,52 @@
+mod common;
+
+use gitvote::methods::{borda, check_ballot};
+use gitvote::schema::{BordaPoints, Schema};
+use gitvote::vote::Ballot;
+use common::options;
+
+#[test]
+fn standard_points_and_position_counts() {
+    let opts = options(&["a", "b", "c"]);
+    let rankings = [options(&["a", "b", "c"]), options(&["b", "a", "c"]), options(&["b", "c"]), options(&["c"])];
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = borda::count(&opts, BordaPoints::Standard, &refs);
+
+    // a: 2 + 1 = 3, b: 1 + 2 + 2 = 5, c: 0 + 0 + 1 + 2 = 3
+    assert_eq!(result.options[0].positions, vec![1, 1, 0]);
+    assert_eq!(result.options[1].points, 5.0);
+    assert_eq!(result.options[2].points, 3.0);
+    assert_eq!(result.winners, options(&["b"]));
+
+    let text = result.to_string();
+    assert!(text.contains("Points per place (Standard): 2, 1, 0\n"));
+    assert!(text.contains("b: 5 points (#1: 2, #2: 1, #3: 0)\n"));
+}
+
+#[test]
+fn dowdall_favours_first_places() {
+    let opts = options(&["a", "b", "c"]);
+    let rankings = [options(&["a", "c", "b"]), options(&["b", "c", "a"]), options(&["a", "c", "b"]), options(&["c", "b", "a"])];
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    // Standard: a 4, b 3, c 5; Dowdall: a 2.667, b 2.167, c 2.5
+    let standard = borda::count(&opts, BordaPoints::Standard, &refs);
+    assert_eq!(standard.winners, options(&["c"]));
+
+    let dowdall = borda::count(&opts, BordaPoints::Dowdall, &refs);
+    assert!((dowdall.options[0].points - 8.0 / 3.0).abs() < 1e-9);
+    assert_eq!(dowdall.winners, options(&["a"]));
+    assert!(dowdall.to_string().contains("a: 2.667 points (#1: 2, #2: 0, #3: 2)"));
+}
+
+#[test]
+fn schema_selects_point_scheme() {
+    let schema: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "borda", "borda_points": "dowdall" }"#,
+    ).unwrap();
+
+    assert_eq!(schema.borda_points, BordaPoints::Dowdall);
+    assert!(check_ballot(&schema, &Ballot::Ranking(options(&["b"]))).is_ok());
+    assert_eq!(check_ballot(&schema, &Ballot::Choice("a".into())).unwrap_err().rule, "wrong-ballot");
+}
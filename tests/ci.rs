@@ -0,0 +1,113 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use gitvote::ci::{detect_event, CiEvent};
+use tempfile::TempDir;
+use common::{as_voter, cast, clone, git, git_output};
+
+fn env_of<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    move |name| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+}
+
+#[test]
+fn detects_event_from_ci_variables() {
+    assert_eq!(detect_event(env_of(&[("GITHUB_EVENT_NAME", "pull_request")])), Some(CiEvent::Pr));
+    assert_eq!(detect_event(env_of(&[("GITHUB_EVENT_NAME", "push")])), Some(CiEvent::Push));
+    assert_eq!(detect_event(env_of(&[("CI_MERGE_REQUEST_IID", "7")])), Some(CiEvent::Pr));
+    assert_eq!(detect_event(env_of(&[("CI_PIPELINE_SOURCE", "push")])), Some(CiEvent::Push));
+    assert_eq!(
+        detect_event(env_of(&[("GITVOTE_EVENT", "pr"), ("GITHUB_EVENT_NAME", "push")])),
+        Some(CiEvent::Pr)
+    );
+    assert_eq!(detect_event(env_of(&[])), None);
+}
+
+#[test]
+fn push_builds_commits_and_pushes_chain() {
+    let central = TempDir::new().unwrap();
+    git(central.path(), &["init", "--bare", "-b", "president"]);
+
+    // A voter's clone: schema plus two votes, pushed to the central repo
+    let voter = admin_clone(central.path());
+    fs::write(voter.path().join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    git(voter.path(), &["add", "schema.json"]);
+    git(voter.path(), &["commit", "-m", "init"]);
+    for (name, choice) in [("alice", "blue"), ("bob", "red")] {
+        cast(voter.path(), name, choice);
+    }
+    git(voter.path(), &["push", "origin", "president"]);
+
+    // The CI job
+    let ci = admin_clone(central.path());
+    let out = gitvote_ci(ci.path(), &[("GITHUB_EVENT_NAME", "push")]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let author = git_output(central.path(), &["log", "-1", "--format=%an <%ae>", "president"]);
+    assert_eq!(author, "gitvote-bot <bot@example.com>");
+    let blocks = git_output(central.path(), &["ls-tree", "--name-only", "president", "blocks/"]);
+    assert_eq!(blocks.lines().count(), 2);
+
+    // Rebuilding an unchanged chain is a no-op
+    let head = git_output(central.path(), &["rev-parse", "president"]);
+    let out = gitvote_ci(ci.path(), &[("GITHUB_EVENT_NAME", "push")]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Chain already up to date"));
+    assert_eq!(git_output(central.path(), &["rev-parse", "president"]), head);
+
+    // Status lines stay off stdout, which carries the report
+    let voter = admin_clone(central.path());
+    cast(voter.path(), "carol", "blue");
+    git(voter.path(), &["push", "-q", "origin", "president"]);
+    git(ci.path(), &["pull", "-q", "--rebase", "origin", "president"]);
+    let out = gitvote_ci_with(ci.path(), &["--format", "sarif"], &[("GITHUB_EVENT_NAME", "push")]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let sarif: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is only the SARIF report");
+    assert_eq!(sarif["version"], "2.1.0");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Chain built with 3 blocks") && stderr.contains("Pushed chain"), "{}", stderr);
+}
+
+#[test]
+fn pr_with_invalid_vote_fails_without_committing() {
+    let central = TempDir::new().unwrap();
+    git(central.path(), &["init", "--bare", "-b", "president"]);
+
+    let pr = admin_clone(central.path());
+    fs::write(pr.path().join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    fs::create_dir(pr.path().join("votes")).unwrap();
+    fs::write(
+        pr.path().join("votes/vote-forged.json"),
+        r#"{ "voter": "mallory", "choice": "blue", "signature": "forged", "timestamp": "2025-06-22T23:55:41Z" }"#,
+    ).unwrap();
+    git(pr.path(), &["add", "."]);
+    git(pr.path(), &["commit", "-m", "forged vote"]);
+
+    let out = gitvote_ci(pr.path(), &[("CI_MERGE_REQUEST_IID", "1")]);
+    assert_eq!(out.status.code(), Some(5));
+    assert!(!pr.path().join("blocks").exists());
+
+    let out = gitvote_ci(pr.path(), &[]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+/// A clone on `president` where commits are made as the admin.
+fn admin_clone(central: &Path) -> TempDir {
+    let dir = clone(central, "president");
+    as_voter(dir.path(), "admin", "admin@example.com");
+    dir
+}
+
+fn gitvote_ci(dir: &Path, vars: &[(&str, &str)]) -> Output {
+    gitvote_ci_with(dir, &[], vars)
+}
+
+fn gitvote_ci_with(dir: &Path, args: &[&str], vars: &[(&str, &str)]) -> Output {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_gitvote"));
+    cmd.args(["ci", "--push"]).args(args).current_dir(dir);
+    for var in ["GITVOTE_EVENT", "GITHUB_EVENT_NAME", "GITHUB_REF_NAME", "CI_MERGE_REQUEST_IID", "CI_PIPELINE_SOURCE", "CI_COMMIT_BRANCH"] {
+        cmd.env_remove(var);
+    }
+    cmd.envs(vars.iter().copied()).output().unwrap()
+}
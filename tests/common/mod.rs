@@ -0,0 +1,176 @@
+//! Fixtures shared by the integration tests: scratch repositories driven
+//! through the `gitvote` binary, and ballots for the methods tested directly.
+
+// Each test crate uses only some of these
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use chrono::{DateTime, Utc};
+use gitvote::block::Block;
+use gitvote::chain;
+use gitvote::schema::Schema;
+use gitvote::utils::generate_fake_signature;
+use gitvote::vote::{Ballot, Vote};
+use tempfile::TempDir;
+
+fn status_and_text(output: Output) -> (i32, String) {
+    let text = String::from_utf8_lossy(&output.stdout).to_string() + &String::from_utf8_lossy(&output.stderr);
+    (output.status.code().unwrap_or(-1), text)
+}
+
+/// Runs the gitvote binary in `dir`, returning its exit code and combined
+/// output.
+pub fn gitvote(dir: &Path, args: &[&str]) -> (i32, String) {
+    status_and_text(
+        Command::new(env!("CARGO_BIN_EXE_gitvote"))
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("failed to run gitvote"),
+    )
+}
+
+/// What `gitvote validate` says about `schema`, which it must reject as
+/// invalid (exit code 4).
+pub fn schema_error(schema: &str) -> String {
+    let tmp = election(schema);
+    let (code, out) = gitvote(tmp.path(), &["validate"]);
+    assert_eq!(code, 4, "{}", out);
+    out
+}
+
+/// Runs the gitvote binary in `dir`, failing the test unless it succeeds,
+/// and returns its combined output.
+pub fn succeed(dir: &Path, args: &[&str]) -> String {
+    let (code, out) = gitvote(dir, args);
+    assert_eq!(code, 0, "gitvote {:?}: {}", args, out);
+    out
+}
+
+/// Like [`gitvote`], answering prompts with `stdin`.
+pub fn gitvote_with_input(dir: &Path, args: &[&str], stdin: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gitvote"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run gitvote");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    status_and_text(child.wait_with_output().unwrap())
+}
+
+/// Runs git in `dir` and fails the test if it fails.
+pub fn git(dir: &Path, args: &[&str]) {
+    let (code, out) = status_and_text(Command::new("git").args(args).current_dir(dir).output().expect("failed to run git"));
+    assert_eq!(code, 0, "git {:?}: {}", args, out);
+}
+
+/// Runs git in `dir` and returns its trimmed stdout.
+pub fn git_output(dir: &Path, args: &[&str]) -> String {
+    let out = Command::new("git").args(args).current_dir(dir).output().expect("failed to run git");
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).unwrap().trim().to_string()
+}
+
+/// Commits everything in the working tree as if at `date`.
+pub fn commit_at(dir: &Path, date: &str) {
+    git(dir, &["add", "-A"]);
+    let output = Command::new("git")
+        .args(["commit", "-q", "-m", "backdated"])
+        .env("GIT_COMMITTER_DATE", date)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Rewrites the chain in `blocks/` as if `build-chain` had recorded every
+/// block at `at`, the way a chain built while the window was open looks.
+pub fn backdate_chain(dir: &Path, at: &str) {
+    let at: DateTime<Utc> = at.parse().unwrap();
+    let mut paths: Vec<_> = fs::read_dir(dir.join("blocks")).unwrap().map(|e| e.unwrap().path()).collect();
+    paths.sort();
+
+    let mut prev_hash = "GENESIS".to_string();
+    for path in paths {
+        let block: Block = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let block = Block { recorded_at: Some(at), prev_hash: Some(prev_hash), ..block };
+        let block = Block { hash: chain::block_hash(&block), ..block };
+        prev_hash = block.hash.clone();
+        fs::write(&path, serde_json::to_string_pretty(&block).unwrap()).unwrap();
+    }
+}
+
+/// A fresh repository holding `schema` as `schema.json`.
+pub fn election(schema: &str) -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "commit.gpgsign", "false"]);
+    fs::write(dir.join("schema.json"), schema).unwrap();
+    tmp
+}
+
+/// A clone of the bare repository `central`, on `branch`.
+pub fn clone(central: &Path, branch: &str) -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    git(dir, &["clone", "-q", central.to_str().unwrap(), "."]);
+    git(dir, &["checkout", "-q", "-B", branch]);
+    git(dir, &["config", "commit.gpgsign", "false"]);
+    tmp
+}
+
+/// Makes `name <email>` the git user, and so the voter.
+pub fn as_voter(dir: &Path, name: &str, email: &str) {
+    git(dir, &["config", "user.name", name]);
+    git(dir, &["config", "user.email", email]);
+}
+
+/// Casts `choice` as `voter` <`voter`@example.com>.
+pub fn cast(dir: &Path, voter: &str, choice: &str) {
+    as_voter(dir, voter, &format!("{}@example.com", voter));
+    let (code, out) = gitvote(dir, &["cast", "--choice", choice]);
+    assert_eq!(code, 0, "{}", out);
+}
+
+/// A correctly signed vote file's content.
+pub fn signed_vote(voter: &str, choice: &str, timestamp: &str) -> String {
+    serde_json::json!({
+        "voter": voter,
+        "choice": choice,
+        "signature": generate_fake_signature(voter, choice),
+        "timestamp": timestamp,
+    })
+    .to_string()
+}
+
+/// Writes `voter`'s vote to `votes/vote-<voter>.json` without committing it.
+pub fn write_vote(dir: &Path, voter: &str, choice: &str) {
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    fs::write(
+        dir.join(format!("votes/vote-{}.json", voter)),
+        signed_vote(voter, choice, "2025-06-22T23:55:41Z"),
+    )
+    .unwrap();
+}
+
+/// Owned copies of option (or grade) names.
+pub fn options(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+/// `ballots` cast by anonymous voters `v0`, `v1`, ..., linked into a chain.
+pub fn blocks(schema: &Schema, ballots: Vec<Ballot>) -> Vec<Block> {
+    let votes = ballots
+        .into_iter()
+        .enumerate()
+        .map(|(i, ballot)| Vote { voter: format!("v{}", i), ballot, signature: String::new(), timestamp: Utc::now() })
+        .collect();
+    chain::link(schema, votes)
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::collections::BTreeMap;
+use gitvote::methods::credits::{self, Cost};
+use gitvote::methods::check_ballot;
+use gitvote::schema::Schema;
+use gitvote::vote::Ballot;
+use common::options;
+
+fn allocation(pairs: &[(&str, u32)]) -> BTreeMap<String, u32> {
+    pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+#[test]
+fn reports_effective_votes_and_credits() {
+    let ballots = [allocation(&[("a", 3), ("b", 1)]), allocation(&[("b", 2)]), allocation(&[("a", 0), ("b", 1)])];
+    let refs: Vec<_> = ballots.iter().map(|b| (b, 1)).collect();
+
+    let result = credits::count(&options(&["a", "b"]), Cost::Quadratic, 10, &refs);
+
+    assert_eq!(result.options[0].votes, 3);
+    assert_eq!(result.options[0].credits, 9);
+    assert_eq!(result.options[0].supporters, 1);
+    assert_eq!(result.options[1].votes, 4);
+    assert_eq!(result.options[1].credits, 6);
+    assert_eq!(result.winners, options(&["b"]));
+    assert!(result.to_string().contains("b: 4 effective votes (6 credits from 3 of 3 ballots)"));
+}
+
+#[test]
+fn rejects_over_budget_ballots() {
+    let quadratic: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "quadratic", "credits": 10 }"#,
+    ).unwrap();
+    let cumulative: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "cumulative", "credits": 10 }"#,
+    ).unwrap();
+
+    // 3 + 1 votes: 10 credits quadratic, 4 cumulative
+    let ballot = Ballot::Allocation(allocation(&[("a", 3), ("b", 1)]));
+    assert!(check_ballot(&quadratic, &ballot).is_ok());
+    assert!(check_ballot(&cumulative, &ballot).is_ok());
+
+    // 3 + 2 votes: 13 credits quadratic, 5 cumulative
+    let ballot = Ballot::Allocation(allocation(&[("a", 3), ("b", 2)]));
+    assert_eq!(check_ballot(&quadratic, &ballot).unwrap_err().rule, "over-budget");
+    assert!(check_ballot(&cumulative, &ballot).is_ok());
+
+    // Costs that would wrap around a u64 are over budget, not tiny
+    let huge = Ballot::Allocation(allocation(&[("a", u32::MAX), ("b", 100_000)]));
+    let schema: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "quadratic", "credits": 100 }"#,
+    ).unwrap();
+    assert_eq!(check_ballot(&schema, &huge).unwrap_err().rule, "over-budget");
+    let refs = [(&allocation(&[("a", u32::MAX)]), u64::MAX)];
+    let result = credits::count(&options(&["a"]), Cost::Quadratic, 100, &refs);
+    assert_eq!(result.options[0].votes, u32::MAX as u128 * u64::MAX as u128);
+
+    let unknown = Ballot::Allocation(allocation(&[("z", 1)]));
+    assert_eq!(check_ballot(&cumulative, &unknown).unwrap_err().rule, "invalid-allocation");
+}
+
+#[test]
+fn allocation_ballots_round_trip() {
+    let vote: gitvote::vote::Vote = serde_json::from_str(
+        r#"{ "voter": "v", "allocation": { "b": 1, "a": 2 }, "signature": "s", "timestamp": "2025-06-22T23:55:41Z" }"#,
+    ).unwrap();
+
+    assert_eq!(vote.ballot.canonical(), "allocate:a=2,b=1");
+    assert_eq!(vote.ballot.to_string(), "allocate a=2, b=1");
+}
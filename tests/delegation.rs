@@ -0,0 +1,174 @@
+mod common;
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use gitvote::delegation::{resolve, End};
+use gitvote::utils::generate_fake_signature;
+use common::{as_voter, backdate_chain, election, git, gitvote, write_vote};
+
+fn write_delegation(dir: &Path, delegator: &str, delegate: &str, scope: &str) {
+    fs::create_dir_all(dir.join("delegations")).unwrap();
+    let delegation = serde_json::json!({
+        "delegator": delegator,
+        "delegate": delegate,
+        "scope": scope,
+        "signature": generate_fake_signature(delegator, &format!("delegate:{}:{}", delegate, scope)),
+        "timestamp": "2025-06-20T10:00:00Z",
+    });
+    fs::write(dir.join(format!("delegations/delegation-{}-{}.json", delegator, scope)), delegation.to_string()).unwrap();
+}
+
+#[test]
+fn resolves_transitively_and_stops_at_direct_voters() {
+    let delegates: BTreeMap<String, String> = [("a", "b"), ("b", "c"), ("c", "d"), ("x", "y"), ("y", "x"), ("n", "m")]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let voted: HashSet<&str> = ["c", "d"].into_iter().collect();
+
+    let resolutions = resolve(&delegates, &voted);
+    let end = |who: &str| resolutions.iter().find(|r| r.delegator == who).map(|r| r.end.clone());
+
+    // b delegated on to c, who voted; c's own delegation is overridden
+    assert_eq!(end("a"), Some(End::Voted("c".into())));
+    assert_eq!(end("b"), Some(End::Voted("c".into())));
+    assert_eq!(end("c"), None);
+    assert_eq!(end("x"), Some(End::Cycle));
+    assert_eq!(end("n"), Some(End::NoVote));
+}
+
+#[test]
+fn tally_reports_direct_and_delegated_weight() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    write_vote(dir, "alice", "blue");
+    write_vote(dir, "bob", "red");
+    write_vote(dir, "dave", "red");
+    write_delegation(dir, "carol", "alice", "global");
+    write_delegation(dir, "erin", "carol", "proposal");
+    // dave voted, so his delegation is ignored
+    write_delegation(dir, "dave", "alice", "proposal");
+
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 3\nred votes: 2\n"), "{}", out);
+    assert!(out.contains("  erin → carol → alice\n"), "{}", out);
+    assert!(out.contains("Direct + delegated weight:\n  blue: 1 + 2\n  red: 2 + 0\n"), "{}", out);
+}
+
+#[test]
+fn proposal_delegation_overrides_global_and_cycles_fail_validation() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    write_vote(dir, "alice", "blue");
+    write_vote(dir, "bob", "red");
+    write_delegation(dir, "carol", "alice", "global");
+    write_delegation(dir, "carol", "bob", "proposal");
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (_, out) = gitvote(dir, &["tally"]);
+    assert!(out.contains("blue votes: 1\nred votes: 2\n"), "{}", out);
+
+    write_delegation(dir, "erin", "frank", "proposal");
+    write_delegation(dir, "frank", "erin", "proposal");
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("Delegation cycle: erin → frank → erin"), "{}", out);
+}
+
+#[test]
+fn the_last_committed_delegation_counts() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"], "public_tally": true }"#);
+    let dir = tmp.path();
+    as_voter(dir, "admin", "admin@example.com");
+
+    write_vote(dir, "alice", "blue");
+    write_vote(dir, "bob", "red");
+    write_delegation(dir, "carol", "bob", "proposal");
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", "first"]);
+
+    // Committed later under an earlier file name and an earlier timestamp
+    let delegation = serde_json::json!({
+        "delegator": "carol",
+        "delegate": "alice",
+        "scope": "proposal",
+        "signature": generate_fake_signature("carol", "delegate:alice:proposal"),
+        "timestamp": "2020-01-01T00:00:00Z",
+    });
+    fs::write(dir.join("delegations/a-redelegation.json"), delegation.to_string()).unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", "redelegate"]);
+
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 0, "{}", out);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (_, out) = gitvote(dir, &["tally"]);
+    assert!(out.contains("blue votes: 2\nred votes: 1\n"), "{}", out);
+
+    // The running tally counts delegations the same way
+    let (code, out) = gitvote(dir, &["report"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("Turnout: 2\nblue votes: 2\nred votes: 1\n"), "{}", out);
+}
+
+#[test]
+fn delegations_must_be_recorded_inside_the_voting_window() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    // Recorded a month before the proposal opens
+    write_delegation(dir, "carol", "alice", "global");
+    write_delegation(dir, "erin", "bob", "proposal");
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    backdate_chain(dir, "2025-05-01T00:00:00Z");
+    fs::write(dir.join("schema.json"), r#"{ "allowed": ["blue", "red"], "opens_at": "2025-06-01T00:00:00Z" }"#).unwrap();
+
+    // A global delegation may predate the proposal; one scoped to it may not
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("Block 1: Delegated at 2025-05-01T00:00:00+00:00 outside the voting window"), "{}", out);
+
+    write_vote(dir, "alice", "blue");
+    write_vote(dir, "bob", "red");
+    let (code, out) = gitvote(dir, &["validate", "--format", "github"]);
+    assert_eq!(code, 5, "{}", out);
+    assert_eq!(out.matches("outside-window").count(), 1, "{}", out);
+    assert!(out.contains("delegation-erin-proposal.json"), "{}", out);
+
+    // Rebuilding the chain leaves it out
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 2\nred votes: 1\n"), "{}", out);
+}
+
+#[test]
+fn delegations_after_the_window_closes_are_not_counted() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    write_vote(dir, "alice", "blue");
+    write_vote(dir, "bob", "red");
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    backdate_chain(dir, "2025-06-20T00:00:00Z");
+    fs::write(dir.join("schema.json"), r#"{ "allowed": ["blue", "red"], "closes_at": "2025-06-30T00:00:00Z" }"#).unwrap();
+
+    // Their timestamps are inside the window, but they are recorded now
+    write_delegation(dir, "carol", "alice", "proposal");
+    write_delegation(dir, "dave", "alice", "global");
+    let (code, out) = gitvote(dir, &["validate", "--format", "github"]);
+    assert_eq!(code, 5, "{}", out);
+    assert_eq!(out.matches("outside-window").count(), 2, "{}", out);
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 1\nred votes: 1\n"), "{}", out);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+use common::{as_voter, cast, clone, git, gitvote};
+
+#[test]
+fn pre_receive_rejects_invalid_votes_on_proposal_branches() {
+    let central = TempDir::new().unwrap();
+    git(central.path(), &["init", "--bare"]);
+    assert_eq!(gitvote(central.path(), &["hook", "install", "--repo", "."]).0, 0);
+
+    let voter = clone(central.path(), "proposal/001-color");
+    as_voter(voter.path(), "alice", "alice@example.com");
+
+    fs::write(voter.path().join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    git(voter.path(), &["add", "schema.json"]);
+    git(voter.path(), &["commit", "-m", "init"]);
+    assert!(push(voter.path(), "proposal/001-color").status.success());
+
+    // A real vote goes through
+    cast(voter.path(), "alice", "blue");
+    assert!(push(voter.path(), "proposal/001-color").status.success());
+
+    // A forged one is rejected with a message pointing at the file
+    fs::write(
+        voter.path().join("votes/vote-forged.json"),
+        r#"{ "voter": "mallory", "choice": "green", "signature": "forged", "timestamp": "2025-06-22T23:55:41Z" }"#,
+    ).unwrap();
+    git(voter.path(), &["add", "votes"]);
+    git(voter.path(), &["commit", "-m", "forged"]);
+
+    let out = push(voter.path(), "proposal/001-color");
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("votes/vote-forged.json:1: Invalid choice 'green'"), "{}", stderr);
+    assert!(stderr.contains("Signature mismatch for voter mallory"), "{}", stderr);
+
+    // Other branches are not policed
+    git(voter.path(), &["checkout", "-b", "scratch"]);
+    assert!(push(voter.path(), "scratch").status.success());
+}
+
+#[test]
+fn pre_receive_skips_commits_without_a_schema() {
+    let central = TempDir::new().unwrap();
+    git(central.path(), &["init", "--bare"]);
+    assert_eq!(gitvote(central.path(), &["hook", "install", "--repo", "."]).0, 0);
+
+    let voter = clone(central.path(), "proposal/002-setup");
+    as_voter(voter.path(), "alice", "alice@example.com");
+
+    // The branch starts before the election is set up
+    fs::write(voter.path().join("README.md"), "Colour vote\n").unwrap();
+    git(voter.path(), &["add", "README.md"]);
+    git(voter.path(), &["commit", "-m", "readme"]);
+    fs::write(voter.path().join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    git(voter.path(), &["add", "schema.json"]);
+    git(voter.path(), &["commit", "-m", "init"]);
+    cast(voter.path(), "alice", "blue");
+
+    let out = push(voter.path(), "proposal/002-setup");
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
+
+#[test]
+fn install_refuses_non_bare_repo_and_foreign_hooks() {
+
+    let work = TempDir::new().unwrap();
+    git(work.path(), &["init"]);
+    assert_eq!(gitvote(work.path(), &["hook", "install"]).0, 2);
+
+    let bare = TempDir::new().unwrap();
+    git(bare.path(), &["init", "--bare"]);
+    fs::write(bare.path().join("hooks/pre-receive"), "#!/bin/sh\nexit 0\n").unwrap();
+    assert_eq!(gitvote(bare.path(), &["hook", "install"]).0, 2);
+    assert_eq!(gitvote(bare.path(), &["hook", "install", "--force"]).0, 0);
+
+    // Re-installing over our own hook needs no --force
+    assert_eq!(gitvote(bare.path(), &["hook", "install"]).0, 0);
+}
+
+#[test]
+fn client_hooks_block_bad_votes_before_they_leave() {
+    let central = TempDir::new().unwrap();
+    git(central.path(), &["init", "--bare"]);
+
+    let voter = clone(central.path(), "proposal/001-color");
+    as_voter(voter.path(), "alice", "alice@example.com");
+    assert_eq!(gitvote(voter.path(), &["hook", "install", "--client"]).0, 0);
+    assert!(voter.path().join(".git/hooks/pre-commit").exists());
+    assert!(voter.path().join(".git/hooks/pre-push").exists());
+
+    fs::write(voter.path().join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    git(voter.path(), &["add", "schema.json"]);
+    git(voter.path(), &["commit", "-m", "init"]);
+    cast(voter.path(), "alice", "blue");
+
+    // Voting twice is stopped by pre-commit
+    let (code, out) = gitvote(voter.path(), &["cast", "--choice", "red"]);
+    assert_ne!(code, 0);
+    assert!(out.contains("Duplicate vote by voter: alice <alice@example.com>"), "{}", out);
+    git(voter.path(), &["reset", "--hard"]);
+    git(voter.path(), &["clean", "-fd"]);
+
+    // Sneaking a forged vote past pre-commit is still caught by pre-push
+    fs::write(
+        voter.path().join("votes/vote-forged.json"),
+        r#"{ "voter": "mallory", "choice": "blue", "signature": "forged", "timestamp": "2025-06-22T23:55:41Z" }"#,
+    ).unwrap();
+    git(voter.path(), &["add", "votes"]);
+    git(voter.path(), &["commit", "--no-verify", "-m", "forged"]);
+
+    let out = push(voter.path(), "proposal/001-color");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Signature mismatch for voter mallory"));
+
+    git(voter.path(), &["reset", "--hard", "HEAD~1"]);
+    assert!(push(voter.path(), "proposal/001-color").status.success());
+}
+
+fn push(dir: &Path, branch: &str) -> Output {
+    Command::new("git")
+        .args(["push", "origin", branch])
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
@@ -1,7 +1,9 @@
-use std::process::{Command, Stdio};
+mod common;
+
 use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
+use common::{as_voter, cast, git, gitvote, succeed};
 
 #[test]
 fn full_end_to_end_flow_with_real_cli() {
@@ -9,38 +11,22 @@ fn full_end_to_end_flow_with_real_cli() {
     let tmp_dir = TempDir::new().expect("failed to create temp dir");
     let repo_path = tmp_dir.path();
 
-    // Find the gitvote binary (always absolute path to avoid path issues)
-    let binary_path = std::fs::canonicalize("./target/release/gitvote")
-        .expect("You must build the binary first: cargo build --release");
-
     // Init Git repo
-    run("git init", repo_path);
-    run("git checkout -b president", repo_path);
-
-    run("git commit --allow-empty -m init", repo_path);
+    init_repo(repo_path);
 
-    // Cast 3 votes using the real gitvote binary
-    cast_vote(repo_path, &binary_path, "blue");
-    cast_vote(repo_path, &binary_path, "red");
-    cast_vote(repo_path, &binary_path, "blue");
+    // Cast 3 votes, as 3 different voters, using the real gitvote binary
+    cast(repo_path, "alice", "blue");
+    cast(repo_path, "bob", "red");
+    cast(repo_path, "carol", "blue");
 
-    // Generate blocks
-    run(
-        &format!("{} generate-blocks --branch president", binary_path.display()),
-        repo_path,
-    );
+    // Validate votes
+    succeed(repo_path, &["validate"]);
 
-    // Validate chain
-    run(
-        &format!("{} validate-chain", binary_path.display()),
-        repo_path,
-    );
+    // Build chain
+    succeed(repo_path, &["build-chain"]);
 
     // Tally votes
-    run(
-        &format!("{} tally", binary_path.display()),
-        repo_path,
-    );
+    succeed(repo_path, &["tally"]);
 
     // Assert blocks written
     let blocks_path = repo_path.join("blocks");
@@ -54,25 +40,117 @@ fn full_end_to_end_flow_with_real_cli() {
     assert_eq!(block_count, 3);
 }
 
-fn cast_vote(repo_path: &Path, binary_path: &Path, choice: &str) {
-    run(
-        &format!("{} cast --choice {}", binary_path.display(), choice),
-        repo_path,
-    );
+#[test]
+fn exit_codes_identify_failure_category() {
+    let tmp_dir = TempDir::new().expect("failed to create temp dir");
+    let repo_path = tmp_dir.path();
+
+    // schema.json missing
+    assert_eq!(gitvote(repo_path, &["validate"]).0, 4);
+
+    // One option cannot be both abstain and blank
+    fs::write(
+        repo_path.join("schema.json"),
+        r#"{ "allowed": ["yes", "skip"], "abstain": "skip", "blank": "skip" }"#,
+    ).unwrap();
+    assert_eq!(gitvote(repo_path, &["validate"]).0, 4);
+
+    init_repo(repo_path);
+    cast(repo_path, "alice", "blue");
+
+    // Invalid choice is rejected before anything is written
+    assert_eq!(gitvote(repo_path, &["cast", "--choice", "green"]).0, 5);
+
+    // Duplicate voter
+    cast(repo_path, "alice", "red");
+    assert_eq!(gitvote(repo_path, &["validate"]).0, 5);
+
+    // No blocks built yet
+    assert_eq!(gitvote(repo_path, &["tally"]).0, 6);
+}
+
+#[test]
+fn tampered_block_is_annotated() {
+    let tmp_dir = TempDir::new().expect("failed to create temp dir");
+    let repo_path = tmp_dir.path();
+
+    init_repo(repo_path);
+    cast(repo_path, "alice", "blue");
+    cast(repo_path, "bob", "red");
+    succeed(repo_path, &["build-chain"]);
+
+    let block_path = repo_path.join("blocks/block-0001.json");
+    let block = fs::read_to_string(&block_path).unwrap();
+    fs::write(&block_path, block.replace("example.com", "evil.example")).unwrap();
+
+    let (code, out) = gitvote(repo_path, &["validate", "--format", "github"]);
+    assert_eq!(code, 6);
+    assert!(out.starts_with("::error file=blocks/block-0001.json,line="), "{}", out);
+    assert!(out.contains("gitvote hash-mismatch"), "{}", out);
+}
+
+#[test]
+fn markdown_report_lists_only_pr_votes() {
+    let tmp_dir = TempDir::new().expect("failed to create temp dir");
+    let repo_path = tmp_dir.path();
+
+    init_repo(repo_path);
+    fs::write(
+        repo_path.join("schema.json"),
+        r#"{ "allowed": ["blue", "red"], "public_tally": true }"#,
+    ).unwrap();
+    git(repo_path, &["commit", "-am", "public"]);
+
+    cast(repo_path, "alice", "blue");
+    git(repo_path, &["branch", "base"]);
+    cast(repo_path, "bob", "red");
+
+    let md = succeed(repo_path, &["report", "--markdown", "--base", "base"]);
+    assert!(md.contains("**Validation:** ✅ passed"));
+    assert!(md.contains("| `bob <bob@example.com>` | ✅ verified | ⚠️ unsigned |"));
+    assert!(!md.contains("alice"));
+    assert!(md.contains("**2** voter(s) so far."));
+    assert!(md.contains("```\nblue votes: 1\nred votes: 1\n```"));
 }
 
-fn run(cmd: &str, dir: &Path) {
-    let mut parts = cmd.split_whitespace();
-    let bin = parts.next().unwrap();
-    let args: Vec<&str> = parts.collect();
+#[test]
+fn ranked_ballots_are_cast_and_counted_by_irv() {
+    let tmp_dir = TempDir::new().expect("failed to create temp dir");
+    let repo_path = tmp_dir.path();
+
+    init_repo(repo_path);
+    fs::write(
+        repo_path.join("schema.json"),
+        r#"{ "allowed": ["blue", "red", "green"], "method": "irv" }"#,
+    ).unwrap();
+    git(repo_path, &["commit", "-am", "irv"]);
+
+    as_voter(repo_path, "alice", "alice@example.com");
+    assert_eq!(gitvote(repo_path, &["cast", "--rank", "blue,blue"]).0, 5);
+    assert_eq!(gitvote(repo_path, &["cast", "--choice", "blue"]).0, 5);
+
+    succeed(repo_path, &["cast", "--rank", "green,red"]);
+    as_voter(repo_path, "bob", "bob@example.com");
+    succeed(repo_path, &["cast", "--rank", "red"]);
+    as_voter(repo_path, "carol", "carol@example.com");
+    succeed(repo_path, &["cast", "--rank", "blue,red"]);
+
+    succeed(repo_path, &["validate"]);
+    succeed(repo_path, &["build-chain"]);
+
+    let stdout = succeed(repo_path, &["tally"]);
+
+    assert!(stdout.contains("Round 1:"), "{}", stdout);
+    assert!(stdout.contains("Winner: red"), "{}", stdout);
+}
 
-    let status = Command::new(bin)
-        .args(args)
-        .current_dir(dir)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .expect("failed to run command");
+fn init_repo(repo_path: &Path) {
+    git(repo_path, &["init", "-q"]);
+    git(repo_path, &["checkout", "-q", "-b", "president"]);
+    as_voter(repo_path, "admin", "admin@example.com");
+    git(repo_path, &["config", "commit.gpgsign", "false"]);
 
-    assert!(status.success(), "command failed: {}", cmd);
+    fs::write(repo_path.join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    git(repo_path, &["add", "schema.json"]);
+    git(repo_path, &["commit", "-m", "init"]);
 }
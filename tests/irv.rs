@@ -0,0 +1,66 @@
+mod common;
+
+use gitvote::methods::{check_ballot, irv};
+use gitvote::schema::Schema;
+use gitvote::vote::Ballot;
+use common::options;
+
+fn ballots(rankings: &[&[&str]]) -> Vec<Vec<String>> {
+    rankings.iter().map(|r| options(r)).collect()
+}
+
+#[test]
+fn transfers_until_majority() {
+    let opts = options(&["alice", "bob", "carol"]);
+    let rankings = ballots(&[
+        &["alice", "bob"],
+        &["alice"],
+        &["bob", "alice"],
+        &["bob", "carol"],
+        &["carol", "bob"],
+    ]);
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = irv::count(&opts, &refs);
+
+    assert_eq!(result.rounds.len(), 2);
+    assert_eq!(result.rounds[0].eliminated.as_deref(), Some("carol"));
+    assert_eq!(result.rounds[1].counts, vec![("alice".to_string(), 2), ("bob".to_string(), 3)]);
+    assert_eq!(result.winner.as_deref(), Some("bob"));
+}
+
+#[test]
+fn exhausted_ballots_leave_the_count() {
+    let opts = options(&["a", "b", "c"]);
+    let rankings = ballots(&[&["a"], &["a"], &["b"], &["b"], &["c"]]);
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = irv::count(&opts, &refs);
+
+    // c goes out and its ballot has nowhere to go; a and b tie 2-2, so b
+    // (later in schema order) is eliminated
+    assert_eq!(result.rounds[1].exhausted, 1);
+    assert!(result.rounds[1].tie);
+    assert_eq!(result.rounds[1].eliminated.as_deref(), Some("b"));
+    assert_eq!(result.winner.as_deref(), Some("a"));
+    assert!(result.to_string().contains("✘ c eliminated\n"));
+}
+
+#[test]
+fn rankings_must_use_allowed_unique_options() {
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a", "b"], "method": "irv" }"#).unwrap();
+
+    assert!(check_ballot(&schema, &Ballot::Ranking(options(&["b", "a"]))).is_ok());
+    assert_eq!(check_ballot(&schema, &Ballot::Ranking(options(&["a", "a"]))).unwrap_err().rule, "invalid-ranking");
+    assert_eq!(check_ballot(&schema, &Ballot::Ranking(options(&["a", "z"]))).unwrap_err().rule, "invalid-ranking");
+    assert_eq!(check_ballot(&schema, &Ballot::Choice("a".into())).unwrap_err().rule, "wrong-ballot");
+}
+
+#[test]
+fn plain_choice_votes_still_parse() {
+    let vote: gitvote::vote::Vote = serde_json::from_str(
+        r#"{ "voter": "v", "choice": "blue", "signature": "s", "timestamp": "2025-06-22T23:55:41Z" }"#,
+    ).unwrap();
+
+    assert_eq!(vote.ballot, Ballot::Choice("blue".into()));
+}
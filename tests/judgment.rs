@@ -0,0 +1,92 @@
+mod common;
+
+use std::collections::BTreeMap;
+use gitvote::methods::{check_ballot, judgment, tally};
+use gitvote::schema::Schema;
+use gitvote::vote::Ballot;
+use common::{blocks, options, schema_error};
+
+fn grades(pairs: &[(&str, &str)]) -> Ballot {
+    Ballot::Grades(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<BTreeMap<_, _>>())
+}
+
+#[test]
+fn shared_median_is_broken_by_majority_value() {
+    let scale = options(&["Poor", "Fair", "Good", "Great"]);
+    // a: Fair, Good, Good, Great; b: Poor, Good, Good, Good
+    let ballots = vec![(vec![1, 0], 1), (vec![2, 2], 2), (vec![3, 2], 1)];
+
+    let result = judgment::count(&options(&["a", "b"]), &scale, &ballots);
+
+    assert_eq!(result.options[0].median, Some(2));
+    assert_eq!(result.options[1].median, Some(2));
+    assert_eq!(result.options[0].majority_value.first(4), vec![2, 2, 1, 3]);
+    assert_eq!(result.options[1].majority_value.first(4), vec![2, 2, 0, 2]);
+    assert_eq!(result.tie_broken_after, Some(2));
+    assert_eq!(result.winner(), Some("a"));
+
+    let text = result.to_string();
+    assert!(text.contains("a: median Good (Poor 0, Fair 1, Good 2, Great 1)\n"));
+    assert!(text.contains("Tie on median broken after removing 2 median grade(s)\n"));
+}
+
+#[test]
+fn majority_values_follow_from_the_distribution_at_any_weight() {
+    // Removing one lower median at a time, as the definition reads
+    fn removed_one_by_one(distribution: &[u64]) -> Vec<usize> {
+        let mut remaining = distribution.to_vec();
+        let mut medians = vec![];
+        for left in (1..=remaining.iter().sum::<u64>()).rev() {
+            let mut seen = 0;
+            let median = remaining.iter().position(|n| { seen += n; seen > (left - 1) / 2 }).unwrap();
+            medians.push(median);
+            remaining[median] -= 1;
+        }
+        medians
+    }
+
+    let distributions: [&[u64]; 5] = [&[1, 1, 1, 1], &[3, 0, 2], &[0, 5, 1, 4], &[2, 2], &[4, 1, 0, 3, 2]];
+    for a in distributions {
+        let value = judgment::MajorityValue::new(a);
+        assert_eq!(value.first(value.len()), removed_one_by_one(a), "{:?}", a);
+        for b in distributions {
+            let expected = removed_one_by_one(a).cmp(&removed_one_by_one(b));
+            assert_eq!(value.cmp(&judgment::MajorityValue::new(b)), expected, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    // Share-count weights: decided without one step per unit of weight
+    let scale = options(&["Poor", "Good", "Great"]);
+    let ballots = vec![(vec![2, 1], 20_000_000_000), (vec![0, 1], 20_000_000_000), (vec![1, 2], 1)];
+    let result = judgment::count(&options(&["a", "b"]), &scale, &ballots);
+    assert_eq!(result.winner(), Some("b"));
+    assert_eq!(result.tie_broken_after, Some(1));
+}
+
+#[test]
+fn identical_distributions_tie() {
+    let scale = options(&["Bad", "Good"]);
+    let ballots = vec![(vec![0, 1], 1), (vec![1, 0], 1)];
+
+    let result = judgment::count(&options(&["a", "b"]), &scale, &ballots);
+
+    assert_eq!(result.ranking, vec![options(&["a", "b"])]);
+    assert_eq!(result.winner(), None);
+}
+
+#[test]
+fn grades_come_from_the_schema_scale() {
+    let schema: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "majority_judgment", "grades": ["Reject", "OK", "Great"] }"#,
+    ).unwrap();
+
+    assert!(check_ballot(&schema, &grades(&[("a", "Great"), ("b", "OK")])).is_ok());
+    assert_eq!(check_ballot(&schema, &grades(&[("a", "Good"), ("b", "OK")])).unwrap_err().rule, "invalid-grade");
+    assert_eq!(check_ballot(&schema, &grades(&[("a", "OK")])).unwrap_err().rule, "invalid-grade");
+
+    let ballots = vec![grades(&[("a", "Great"), ("b", "OK")]), grades(&[("a", "OK"), ("b", "OK")])];
+    assert!(tally(&schema, &blocks(&schema, ballots)).unwrap().text.contains("Winner: a"));
+
+    let out = schema_error(r#"{ "allowed": ["a", "b"], "method": "majority_judgment", "grades": [] }"#);
+    assert!(out.contains("\"grades\" needs at least one grade"), "{}", out);
+}
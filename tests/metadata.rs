@@ -0,0 +1,147 @@
+mod common;
+
+use std::fs;
+use common::{as_voter, backdate_chain, commit_at, election, gitvote, signed_vote};
+
+const RICH: &str = r#"{
+    "version": 2,
+    "proposal_id": "RFC-042",
+    "title": "Adopt the 2026 budget",
+    "description": "Fund the roadmap agreed at the summit.",
+    "options": [
+        { "name": "yes", "label": "Adopt it", "description": "as drafted" },
+        { "name": "no", "label": "Reject it" }
+    ],
+    "opens_at": "2025-01-01T00:00:00Z",
+    "eligibility": { "email_domains": ["example.com"] }
+}"#;
+
+#[test]
+fn cast_validate_and_tally_show_the_same_parameters() {
+    let tmp = election(RICH);
+    let dir = tmp.path();
+
+    as_voter(dir, "alice", "alice@example.com");
+    let (code, cast) = gitvote(dir, &["cast", "--choice", "yes"]);
+    assert_eq!(code, 0, "{}", cast);
+    let (code, validate) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 0, "{}", validate);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (code, tally) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", tally);
+
+    for out in [&cast, &validate, &tally] {
+        assert!(out.contains("Proposal RFC-042: Adopt the 2026 budget\n  Fund the roadmap"), "{}", out);
+        assert!(out.contains("Method: plurality\nOptions:\n  yes: Adopt it (as drafted)\n  no: Reject it\n"), "{}", out);
+        assert!(out.contains("Voting window: opens 2025-01-01T00:00:00+00:00\n"), "{}", out);
+        assert!(out.contains("Eligible: emails at example.com\n"), "{}", out);
+    }
+    assert!(tally.contains("yes votes: 1\nno votes: 0\n"), "{}", tally);
+}
+
+#[test]
+fn votes_outside_the_window_are_refused_everywhere() {
+    let tmp = election(r#"{ "allowed": ["yes", "no"], "closes_at": "2025-06-30T00:00:00Z" }"#);
+    let dir = tmp.path();
+
+    as_voter(dir, "alice", "alice@example.com");
+    let (code, out) = gitvote(dir, &["cast", "--choice", "yes"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("outside the voting window"), "{}", out);
+
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    fs::write(
+        dir.join("votes/vote-late.json"),
+        signed_vote("alice <alice@example.com>", "yes", "2025-06-01T00:00:00Z"),
+    )
+    .unwrap();
+
+    // The timestamp claims the window was open; the vote is judged by when
+    // the chain records it, which is now
+    let (code, out) = gitvote(dir, &["validate", "--format", "github"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("outside-window"), "{}", out);
+
+    // So is a commit dated inside the window: voters set commit dates too
+    commit_at(dir, "2025-06-01T00:00:00Z");
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("outside the voting window"), "{}", out);
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("Block 0: Cast at "), "{}", out);
+    assert!(!out.contains("Cast at 2025-06-01"), "{}", out);
+}
+
+#[test]
+fn votes_recorded_in_the_window_stay_valid_after_it_closes() {
+    let tmp = election(r#"{ "allowed": ["yes", "no"], "closes_at": "2025-06-30T00:00:00Z" }"#);
+    let dir = tmp.path();
+    as_voter(dir, "admin", "admin@example.com");
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    fs::write(dir.join("votes/vote-a.json"), signed_vote("alice", "yes", "2025-06-01T00:00:00Z")).unwrap();
+    commit_at(dir, "2025-06-01T00:00:00Z");
+
+    // A chain built while the window was open
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    backdate_chain(dir, "2025-06-02T00:00:00Z");
+
+    // Rebuilding keeps the recorded time of blocks already built
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let block = fs::read_to_string(dir.join("blocks/block-0000.json")).unwrap();
+    assert!(block.contains("\"recorded_at\": \"2025-06-02T00:00:00Z\""), "{}", block);
+
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 0, "{}", out);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("yes votes: 1"), "{}", out);
+
+    // A late vote is not, and is stamped with the real time
+    fs::write(dir.join("votes/vote-b.json"), signed_vote("bob", "no", "2025-06-01T00:00:00Z")).unwrap();
+    commit_at(dir, "2025-06-01T00:00:00Z");
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("vote-b.json"), "{}", out);
+    assert!(!out.contains("vote-a.json"), "{}", out);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    assert_eq!(gitvote(dir, &["tally"]).0, 5);
+}
+
+#[test]
+fn eligibility_and_schema_consistency_are_enforced() {
+    let tmp = election(
+        r#"{
+            "allowed": ["yes", "no"],
+            "voters": ["alice@example.com", "carol <carol@elsewhere.org>"],
+            "eligibility": { "roll_only": true, "email_domains": ["example.com"] }
+        }"#,
+    );
+    let dir = tmp.path();
+
+    as_voter(dir, "bob", "bob@example.com");
+    let (code, out) = gitvote(dir, &["cast", "--choice", "no"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("bob <bob@example.com> is not on the voter roll"), "{}", out);
+
+    as_voter(dir, "carol", "carol@elsewhere.org");
+    let (code, out) = gitvote(dir, &["cast", "--choice", "no"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("does not vote with an email at example.com"), "{}", out);
+
+    as_voter(dir, "alice", "alice@example.com");
+    let (code, out) = gitvote(dir, &["cast", "--choice", "yes"]);
+    assert_eq!(code, 0, "{}", out);
+
+    fs::write(dir.join("schema.json"), r#"{ "allowed": ["yes"], "options": [{ "name": "no" }] }"#).unwrap();
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 4, "{}", out);
+    assert!(out.contains("\"allowed\" and \"options\" list different options"), "{}", out);
+
+    fs::write(dir.join("schema.json"), r#"{ "version": 3, "allowed": ["yes", "no"] }"#).unwrap();
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 4, "{}", out);
+    assert!(out.contains("reads up to version 2"), "{}", out);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use gitvote::block::Block;
+use gitvote::error::Result;
+use gitvote::methods::{self, BallotError, Outcome, VotingMethod};
+use gitvote::schema::{Method, Schema};
+use gitvote::vote::Ballot;
+use common::blocks;
+
+/// Counts how many voters picked the first allowed option.
+struct FirstOrNothing;
+
+impl VotingMethod for FirstOrNothing {
+    fn name(&self) -> &str {
+        "first_or_nothing"
+    }
+
+    fn check_ballot(&self, _schema: &Schema, ballot: &Ballot) -> std::result::Result<(), BallotError> {
+        match ballot {
+            Ballot::Choice(_) => Ok(()),
+            _ => Err(BallotError { rule: "wrong-ballot", message: "choices only".into() }),
+        }
+    }
+
+    fn tally(&self, schema: &Schema, blocks: &mut dyn Iterator<Item = &Block>) -> Result<Outcome> {
+        let first = &schema.allowed[0];
+        let n = blocks.filter(|b| b.ballot == Ballot::Choice(first.clone())).count();
+        Ok(Outcome::new(format!("{} picked {}\n", n, first), vec![first.clone()]))
+    }
+}
+
+/// Blocks for plain `choices`.
+fn chosen(schema: &Schema, choices: &[&str]) -> Vec<Block> {
+    blocks(schema, choices.iter().map(|c| Ballot::Choice(c.to_string())).collect())
+}
+
+#[test]
+fn schema_selects_builtin_methods_by_name() {
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a", "b"] }"#).unwrap();
+    assert_eq!(schema.method, Method::Plurality);
+
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a", "b"], "method": "majority_judgment" }"#).unwrap();
+    assert_eq!(schema.method, Method::MajorityJudgment);
+    assert_eq!(serde_json::to_value(&schema).unwrap()["method"], "majority_judgment");
+
+    for method in Method::BUILTIN {
+        assert!(methods::lookup(method.name()).is_some(), "{} is registered", method.name());
+    }
+}
+
+#[test]
+fn registered_methods_validate_and_count() {
+    methods::register(FirstOrNothing);
+
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a", "b"], "method": "first_or_nothing" }"#).unwrap();
+    assert_eq!(schema.method, Method::Custom("first_or_nothing".into()));
+
+    assert!(methods::check_ballot(&schema, &Ballot::Choice("z".into())).is_ok());
+    assert_eq!(
+        methods::check_ballot(&schema, &Ballot::Ranking(vec![])).unwrap_err().rule,
+        "wrong-ballot"
+    );
+    let outcome = methods::tally(&schema, &chosen(&schema, &["a", "b", "a"])).unwrap();
+    assert_eq!(outcome.text, "2 picked a\n");
+    assert_eq!(outcome.winners, vec!["a".to_string()]);
+}
+
+#[test]
+fn unknown_methods_are_rejected() {
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a"], "method": "nonesuch" }"#).unwrap();
+
+    assert_eq!(methods::check_ballot(&schema, &Ballot::Choice("a".into())).unwrap_err().rule, "unknown-method");
+    let err = methods::tally(&schema, &chosen(&schema, &["a"])).unwrap_err();
+    assert_eq!(err.exit_code(), 4);
+    assert!(err.to_string().contains("Unknown voting method 'nonesuch'"));
+}
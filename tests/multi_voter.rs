@@ -7,12 +7,16 @@ use std::os::unix::fs::PermissionsExt;
 #[test]
 fn full_multi_voter_protocol() {
     // Locate gitvote binary
-    let binary_path = std::fs::canonicalize("./target/release/gitvote")
-        .expect("Build gitvote first: cargo build --release");
+    let binary_path = PathBuf::from(env!("CARGO_BIN_EXE_gitvote"));
 
-    // Create central bare repo
+    // Create central bare repo with the election branch and its schema
     let central_repo = TempDir::new().unwrap();
     run("git init --bare", central_repo.path());
+    seed_election(central_repo.path());
+    run(
+        "git symbolic-ref HEAD refs/heads/president",
+        central_repo.path(),
+    );
 
     // Simulate 3 distinct voters
     let voter1 = TestVoter::new("alice", central_repo.path(), &binary_path);
@@ -20,10 +24,6 @@ fn full_multi_voter_protocol() {
     let voter3 = TestVoter::new("carol", central_repo.path(), &binary_path);
 
     voter1.cast_vote("blue");
-    run(
-        "git symbolic-ref HEAD refs/heads/president",
-        central_repo.path(),
-    );
     voter2.cast_vote("red");
     voter3.cast_vote("blue");
 
@@ -36,12 +36,12 @@ fn full_multi_voter_protocol() {
 
     // Run full admin flow
     run_in(
-        &format!("{} generate-blocks --branch president", binary_path.display()),
+        &format!("{} validate", binary_path.display()),
         admin_dir.path(),
     );
 
     run_in(
-        &format!("{} validate-chain", binary_path.display()),
+        &format!("{} build-chain", binary_path.display()),
         admin_dir.path(),
     );
 
@@ -62,10 +62,9 @@ fn full_multi_voter_protocol() {
 
 /// Fully isolated simulated voter
 struct TestVoter {
-    name: String,
-    gpg_dir: TempDir,
+    /// Held only so the GPG homedir outlives the voter's commits
+    _gpg_dir: TempDir,
     git_dir: TempDir,
-    key_id: String,
     binary_path: PathBuf,
 }
 
@@ -83,13 +82,9 @@ impl TestVoter {
             Path::new("."),
         );
 
-        // Create election branch inside clone
-        run("git checkout -b president", git_dir.path());
-        run("git commit --allow-empty -m init", git_dir.path());
-        run("git push -u origin president", git_dir.path());
-
         // Configure Git for this voter
         run_in(&format!("git config user.name {}", name), git_dir.path());
+        run_in(&format!("git config user.email {}@example.com", name), git_dir.path());
         run_in(&format!("git config user.signingkey {}", key_id), git_dir.path());
         run_in("git config commit.gpgsign true", git_dir.path());
 
@@ -106,10 +101,8 @@ impl TestVoter {
         run_in(&format!("git config gpg.program {}", wrapper_path.display()), git_dir.path());
 
         Self {
-            name: name.to_string(),
-            gpg_dir,
+            _gpg_dir: gpg_dir,
             git_dir,
-            key_id,
             binary_path: binary_path.to_path_buf(),
         }
     }
@@ -125,6 +118,28 @@ impl TestVoter {
     }
 }
 
+/// Pushes the `president` branch with its `schema.json` to the central repo.
+fn seed_election(central_repo: &Path) {
+    let admin_dir = TempDir::new().unwrap();
+    run(
+        &format!("git clone {} {}", central_repo.display(), admin_dir.path().display()),
+        Path::new("."),
+    );
+
+    run_in("git checkout -b president", admin_dir.path());
+    run_in("git config user.name admin", admin_dir.path());
+    run_in("git config user.email admin@example.com", admin_dir.path());
+    run_in("git config commit.gpgsign false", admin_dir.path());
+
+    fs::write(
+        admin_dir.path().join("schema.json"),
+        r#"{ "allowed": ["blue", "red"] }"#,
+    ).unwrap();
+    run_in("git add schema.json", admin_dir.path());
+    run_in("git commit -m init", admin_dir.path());
+    run_in("git push -u origin president", admin_dir.path());
+}
+
 fn generate_gpg_key(name: &str, homedir: &Path) -> String {
     let batch = format!(
         "Key-Type: RSA
@@ -139,15 +154,19 @@ Expire-Date: 0
     let batch_file = homedir.join("batch");
     fs::write(&batch_file, batch).unwrap();
 
-    let status = Command::new("gpg")
-        .args([
-            "--batch", "--generate-key",
-            "--homedir", homedir.to_str().unwrap(),
-            batch_file.to_str().unwrap(),
-        ])
-        .status()
-        .unwrap();
-    assert!(status.success(), "GPG key generation failed");
+    // gpg occasionally crashes while its agent is starting up; retry a few times
+    let generated = (0..3).any(|_| {
+        Command::new("gpg")
+            .args([
+                "--batch", "--generate-key",
+                "--homedir", homedir.to_str().unwrap(),
+                batch_file.to_str().unwrap(),
+            ])
+            .status()
+            .unwrap()
+            .success()
+    });
+    assert!(generated, "GPG key generation failed");
 
     let output = Command::new("gpg")
         .args([
@@ -0,0 +1,88 @@
+mod common;
+
+use std::collections::BTreeMap;
+use gitvote::methods::check_ballot;
+use gitvote::schema::{Method, Schema};
+use gitvote::vote::Ballot;
+use common::{as_voter, election, gitvote_with_input as gitvote};
+
+const SCHEMA: &str = r#"{
+    "questions": [
+        { "id": "q1", "text": "Adopt the budget?", "allowed": ["yes", "no"], "majority": "unanimous" },
+        { "id": "q2", "text": "Which logo?", "allowed": ["a", "b", "c"], "method": "irv" }
+    ],
+    "voters": ["alice", "bob", "carol"],
+    "quorum": { "voters": 2 },
+    "revote": "last_wins"
+}"#;
+
+fn answers(pairs: &[(&str, Ballot)]) -> Ballot {
+    Ballot::Answers(pairs.iter().map(|(q, b)| (q.to_string(), b.clone())).collect::<BTreeMap<_, _>>())
+}
+
+#[test]
+fn questions_take_their_own_rules_and_the_proposals_roll() {
+    let schema: Schema = serde_json::from_str(SCHEMA).unwrap();
+    let q2 = schema.for_question(schema.question("q2").unwrap());
+
+    assert_eq!(q2.method, Method::Irv);
+    assert_eq!(q2.allowed, vec!["a", "b", "c"]);
+    assert_eq!(q2.voters, schema.voters);
+    assert_eq!(q2.quorum, schema.quorum);
+    assert_eq!(q2.revote, schema.revote);
+    assert!(q2.questions.is_none());
+}
+
+#[test]
+fn each_answer_is_checked_against_its_question() {
+    let schema: Schema = serde_json::from_str(SCHEMA).unwrap();
+    let yes = Ballot::Choice("yes".into());
+    let ranked = Ballot::Ranking(vec!["b".into(), "a".into()]);
+
+    assert!(check_ballot(&schema, &answers(&[("q1", yes.clone()), ("q2", ranked.clone())])).is_ok());
+    // Questions may be skipped
+    assert!(check_ballot(&schema, &answers(&[("q2", ranked.clone())])).is_ok());
+
+    let err = check_ballot(&schema, &answers(&[("q1", ranked.clone())])).unwrap_err();
+    assert_eq!(err.rule, "wrong-ballot");
+    assert!(err.message.starts_with("q1: "));
+
+    assert_eq!(check_ballot(&schema, &answers(&[("q9", yes.clone())])).unwrap_err().rule, "invalid-answers");
+    assert_eq!(check_ballot(&schema, &answers(&[])).unwrap_err().rule, "invalid-answers");
+    assert_eq!(check_ballot(&schema, &yes).unwrap_err().rule, "wrong-ballot");
+}
+
+#[test]
+fn one_vote_answers_several_questions() {
+    let tmp = election(SCHEMA);
+    let dir = tmp.path();
+
+    as_voter(dir, "alice", "alice@example.com");
+    let (code, out) = gitvote(dir, &["cast", "--answer", "q1=yes", "--answer", "q2=b,a"], "");
+    assert_eq!(code, 0, "{}", out);
+    assert_eq!(gitvote(dir, &["cast", "--answer", "q9=yes"], "").0, 5);
+    assert_eq!(gitvote(dir, &["cast", "--choice", "yes"], "").0, 5);
+
+    // Asked interactively; an empty line skips a question
+    as_voter(dir, "bob", "bob@example.com");
+    let (code, out) = gitvote(dir, &["cast"], "yes\n\n");
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("q1: Adopt the budget?\n  Options: yes, no\n"), "{}", out);
+
+    as_voter(dir, "carol", "carol@example.com");
+    let (code, out) = gitvote(dir, &["cast", "--answer", "q1=no", "--answer", "q2=b"], "");
+    assert_eq!(code, 0, "{}", out);
+
+    assert_eq!(gitvote(dir, &["validate"], "").0, 0);
+    assert_eq!(gitvote(dir, &["build-chain"], "").0, 0);
+
+    let (code, out) = gitvote(dir, &["tally"], "");
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("Question q1: Adopt the budget?\nyes votes: 2\nno votes: 1\n"), "{}", out);
+    assert!(out.contains("Question q2: Which logo?\n"), "{}", out);
+    assert!(out.contains("Winner: b"), "{}", out);
+
+    // q1 needs unanimity, q2 a simple majority of those who answered it
+    assert!(out.contains("Question q1:\nVerdict: FAILED\n"), "{}", out);
+    assert!(out.contains("Question q2:\nVerdict: PASSED\n  Turnout: 2 (quorum 2)"), "{}", out);
+}
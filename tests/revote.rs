@@ -0,0 +1,75 @@
+mod common;
+
+use std::fs;
+use tempfile::TempDir;
+use common::{cast, election, gitvote};
+
+/// alice votes blue, bob red, then alice changes to red.
+fn revoted(policy: &str) -> TempDir {
+    let tmp = election(&format!(r#"{{ "allowed": ["blue", "red"]{} }}"#, policy));
+    let dir = tmp.path();
+
+    cast(dir, "alice", "blue");
+    cast(dir, "bob", "red");
+    cast(dir, "alice", "red");
+    tmp
+}
+
+#[test]
+fn last_vote_wins_by_commit_order() {
+    let tmp = revoted(r#", "revote": "last_wins""#);
+    let dir = tmp.path();
+
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let built = fs::read_to_string(dir.join("blocks/block-0000.json")).unwrap();
+
+    // Changing a vote again adds a block and leaves the built ones as they were
+    cast(dir, "alice", "blue");
+    cast(dir, "alice", "red");
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    assert_eq!(fs::read_to_string(dir.join("blocks/block-0000.json")).unwrap(), built);
+
+    let first: serde_json::Value = serde_json::from_str(&built).unwrap();
+    assert_eq!(first["choice"], "blue");
+    assert!(first.get("superseded_by").is_none());
+    let last: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("blocks/block-0004.json")).unwrap()).unwrap();
+    assert_eq!(last["supersedes"], 3);
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 0\nred votes: 2\n"), "{}", out);
+    assert!(out.contains("  block 0 by alice <alice@example.com> (blue), replaced by block 2\n"), "{}", out);
+    assert!(out.contains("  block 3 by alice <alice@example.com> (blue), replaced by block 4\n"), "{}", out);
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+}
+
+#[test]
+fn first_vote_wins() {
+    let tmp = revoted(r#", "revote": "first_wins""#);
+    let dir = tmp.path();
+
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 1\nred votes: 1\n"), "{}", out);
+    assert!(out.contains("  block 2 by alice <alice@example.com> (red), replaced by block 0\n"), "{}", out);
+}
+
+#[test]
+fn revotes_are_rejected_by_validate_and_tally_alike() {
+    let tmp = revoted("");
+    let dir = tmp.path();
+
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("Duplicate vote by voter: alice"), "{}", out);
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("does not allow revoting"), "{}", out);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use gitvote::methods::schulze;
+use common::options;
+
+/// Expands `(count, "ACBED")` groups into one ranking per voter.
+fn ballots(groups: &[(usize, &str)]) -> Vec<Vec<String>> {
+    groups
+        .iter()
+        .flat_map(|(n, order)| std::iter::repeat_n(order.chars().map(|c| c.to_string()).collect(), *n))
+        .collect()
+}
+
+#[test]
+fn resolves_cycle_from_reference_example() {
+    // The 45-voter example from Schulze's paper
+    let rankings = ballots(&[
+        (5, "ACBED"), (5, "ADECB"), (8, "BEDAC"), (3, "CABED"),
+        (7, "CAEBD"), (2, "CBADE"), (7, "DCEBA"), (8, "EBADC"),
+    ]);
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = schulze::count(&options(&["A", "B", "C", "D", "E"]), &refs);
+
+    assert_eq!(result.pairwise[0][1], 20);
+    assert_eq!(result.pairwise[1][0], 25);
+    assert_eq!(result.paths[0], vec![0, 28, 28, 30, 24]);
+    assert_eq!(result.paths[4], vec![25, 28, 28, 31, 0]);
+    assert_eq!(result.condorcet_winner, None);
+    assert_eq!(
+        result.ranking,
+        vec![options(&["E"]), options(&["A"]), options(&["C"]), options(&["B"]), options(&["D"])]
+    );
+    assert_eq!(result.winner(), Some("E"));
+}
+
+#[test]
+fn reports_condorcet_winner_and_treats_unranked_as_last() {
+    let rankings = [options(&["a"]), options(&["a", "b"]), options(&["c", "b"])];
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = schulze::count(&options(&["a", "b", "c"]), &refs);
+
+    // b and c are unranked on the first ballot, so neither gains from it
+    assert_eq!(result.pairwise[1][2], 1);
+    assert_eq!(result.pairwise[2][1], 1);
+    assert_eq!(result.condorcet_winner.as_deref(), Some("a"));
+
+    let printed = result.to_string();
+    assert!(printed.contains("Condorcet winner: a"));
+    assert!(printed.contains("2. b, c (tied)"));
+}
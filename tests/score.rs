@@ -0,0 +1,69 @@
+mod common;
+
+use std::collections::BTreeMap;
+use gitvote::methods::{check_ballot, score};
+use gitvote::schema::{Schema, Unscored};
+use gitvote::vote::Ballot;
+use common::{options, schema_error};
+
+fn scores(pairs: &[(&str, i32)]) -> BTreeMap<String, i32> {
+    pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+#[test]
+fn reports_totals_and_means() {
+    let ballots = [scores(&[("a", 5), ("b", 2)]), scores(&[("a", 1), ("b", 4)]), scores(&[("a", 3), ("b", 3)])];
+    let refs: Vec<_> = ballots.iter().map(|b| (b, 1)).collect();
+
+    let result = score::count(&options(&["a", "b"]), 0, Unscored::Reject, &refs);
+
+    assert_eq!(result.scores[0].total, 9);
+    assert_eq!(result.scores[1].total, 9);
+    assert_eq!(result.winners, options(&["a", "b"]));
+    assert!(result.to_string().contains("a: total 9, mean 3.00 (3 of 3 ballots)"));
+}
+
+#[test]
+fn unscored_options_count_as_min_or_are_ignored() {
+    let ballots = [scores(&[("a", 4)]), scores(&[("a", 2), ("b", 5)])];
+    let refs: Vec<_> = ballots.iter().map(|b| (b, 1)).collect();
+    let opts = options(&["a", "b"]);
+
+    let as_min = score::count(&opts, 1, Unscored::Min, &refs);
+    assert_eq!(as_min.scores[1].total, 6);
+    assert_eq!(as_min.scores[1].mean, Some(3.0));
+    assert_eq!(as_min.winners, options(&["a", "b"]));
+
+    let ignored = score::count(&opts, 1, Unscored::Ignore, &refs);
+    assert_eq!(ignored.scores[1].scored_by, 1);
+    assert_eq!(ignored.winners, options(&["b"]));
+}
+
+#[test]
+fn enforces_range_and_completeness() {
+    let schema: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "score", "score_range": [1, 10] }"#,
+    ).unwrap();
+
+    assert!(check_ballot(&schema, &Ballot::Scores(scores(&[("a", 10), ("b", 1)]))).is_ok());
+    assert!(check_ballot(&schema, &Ballot::Scores(scores(&[("a", 11), ("b", 1)]))).is_err());
+    assert!(check_ballot(&schema, &Ballot::Scores(scores(&[("a", 0), ("b", 1)]))).is_err());
+    assert!(check_ballot(&schema, &Ballot::Scores(scores(&[("a", 5)]))).is_err());
+
+    let lenient: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b"], "method": "score", "unscored": "ignore" }"#,
+    ).unwrap();
+    assert!(check_ballot(&lenient, &Ballot::Scores(scores(&[("a", 5)]))).is_ok());
+
+    let out = schema_error(r#"{ "allowed": ["a", "b"], "method": "score", "score_range": [10, 1] }"#);
+    assert!(out.contains("\"score_range\" [10, 1] has its minimum above its maximum"), "{}", out);
+}
+
+#[test]
+fn heavy_weights_and_extreme_scores_do_not_overflow() {
+    let ballots = [scores(&[("a", i32::MAX)]), scores(&[("a", i32::MAX)])];
+    let refs: Vec<_> = ballots.iter().map(|b| (b, 1_000_000_000)).collect();
+
+    let result = score::count(&options(&["a"]), 0, Unscored::Reject, &refs);
+    assert_eq!(result.scores[0].total, i32::MAX as i128 * 2_000_000_000);
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use gitvote::methods::{check_ballot, stv};
+use gitvote::schema::Schema;
+use gitvote::vote::Ballot;
+use common::{options, schema_error};
+
+fn ballots(groups: &[(usize, &[&str])]) -> Vec<Vec<String>> {
+    groups
+        .iter()
+        .flat_map(|(n, ranking)| std::iter::repeat_n(options(ranking), *n))
+        .collect()
+}
+
+#[test]
+fn surplus_transfers_at_fractional_weight() {
+    let opts = options(&["ann", "ben", "cat"]);
+    let rankings = ballots(&[(6, &["ann", "ben"]), (2, &["cat"]), (1, &["ben"])]);
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = stv::count(&opts, 2, &refs);
+
+    // 9 ballots, 2 seats: quota 4. ann's 6 votes carry a surplus of 2, so
+    // her six ballots move on to ben at 1/3 each, lifting him past cat
+    assert_eq!(result.quota, 4);
+    let first = &result.rounds[0].elected[0];
+    assert_eq!(first.option, "ann");
+    assert!((first.transfer_factor - 1.0 / 3.0).abs() < 1e-9);
+
+    let ben = result.rounds[1].counts.iter().find(|(o, _)| o == "ben").unwrap().1;
+    assert!((ben - 3.0).abs() < 1e-9);
+    assert_eq!(result.rounds[1].eliminated.as_deref(), Some("cat"));
+    assert_eq!(result.elected, vec!["ann", "ben"]);
+}
+
+#[test]
+fn remaining_options_fill_open_seats() {
+    let opts = options(&["a", "b", "c"]);
+    let rankings = ballots(&[(2, &["a"]), (1, &["b"]), (1, &["c"])]);
+    let refs: Vec<(&[String], u64)> = rankings.iter().map(|r| (r.as_slice(), 1)).collect();
+
+    let result = stv::count(&opts, 2, &refs);
+
+    // a meets the quota of 2 at once; b and c then tie for last, so c
+    // (later in schema order) goes and b fills the last seat
+    assert_eq!(result.rounds[0].elected[0].option, "a");
+    assert_eq!(result.rounds[1].eliminated.as_deref(), Some("c"));
+    assert_eq!(result.rounds[2].filled, vec!["b"]);
+    assert_eq!(result.elected, vec!["a", "b"]);
+    assert!(result.to_string().contains("Seats: 2, Droop quota: 2\n"));
+    assert!(result.to_string().ends_with("Elected: a, b\n"));
+}
+
+#[test]
+fn stv_takes_ranked_ballots() {
+    let schema: Schema = serde_json::from_str(r#"{ "allowed": ["a", "b"], "method": "stv", "seats": 2 }"#).unwrap();
+
+    assert_eq!(schema.seats(), 2);
+    assert!(check_ballot(&schema, &Ballot::Ranking(options(&["b", "a"]))).is_ok());
+    assert_eq!(check_ballot(&schema, &Ballot::Choice("a".into())).unwrap_err().rule, "wrong-ballot");
+}
+
+#[test]
+fn seats_must_be_fillable() {
+    let out = schema_error(r#"{ "allowed": ["a", "b"], "method": "stv", "seats": 0 }"#);
+    assert!(out.contains("\"seats\" must be at least 1"), "{}", out);
+    let out = schema_error(r#"{ "allowed": ["a", "b", "abstain"], "abstain": "abstain", "method": "stv", "seats": 3 }"#);
+    assert!(out.contains("3 seats cannot be filled from 2 candidates"), "{}", out);
+}
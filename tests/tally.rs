@@ -1,6 +1,7 @@
 // tests/tally.rs
 
 use gitvote::block::Block;
+use gitvote::vote::Ballot;
 use chrono::Utc;
 use std::collections::HashMap;
 
@@ -10,30 +11,56 @@ fn test_tally_votes() {
         Block {
             index: 0,
             timestamp: Utc::now(),
-            choice: "blue".to_string(),
+            ballot: Ballot::Choice("blue".to_string()),
             voter: "voter1".to_string(),
             prev_hash: None,
+            hash: String::new(),
+            signature: String::new(),
+            weight: None,
+            superseded_by: None,
+            supersedes: None,
+            withdraws: None,
+            delegation: None,
+            recorded_at: None,
         },
         Block {
             index: 1,
             timestamp: Utc::now(),
-            choice: "red".to_string(),
+            ballot: Ballot::Choice("red".to_string()),
             voter: "voter2".to_string(),
             prev_hash: Some("dummy".to_string()),
+            hash: String::new(),
+            signature: String::new(),
+            weight: None,
+            superseded_by: None,
+            supersedes: None,
+            withdraws: None,
+            delegation: None,
+            recorded_at: None,
         },
         Block {
             index: 2,
             timestamp: Utc::now(),
-            choice: "blue".to_string(),
+            ballot: Ballot::Choice("blue".to_string()),
             voter: "voter3".to_string(),
             prev_hash: Some("dummy".to_string()),
+            hash: String::new(),
+            signature: String::new(),
+            weight: None,
+            superseded_by: None,
+            supersedes: None,
+            withdraws: None,
+            delegation: None,
+            recorded_at: None,
         },
     ];
 
     let mut tally: HashMap<String, u64> = HashMap::new();
 
     for block in &blocks {
-        *tally.entry(block.choice.clone()).or_insert(0) += 1;
+        if let Ballot::Choice(choice) = &block.ballot {
+            *tally.entry(choice.clone()).or_insert(0) += 1;
+        }
     }
 
     assert_eq!(*tally.get("blue").unwrap(), 2);
@@ -0,0 +1,130 @@
+mod common;
+
+use std::fs;
+use tempfile::TempDir;
+use common::{as_voter, commit_at, election, git, git_output, gitvote, signed_vote};
+
+/// alice and bob split blue/red; bob voted first, though alice's vote
+/// claims an earlier time.
+fn tied_election(extra: &str) -> TempDir {
+    let tmp = election(&format!(r#"{{ "allowed": ["blue", "red"]{} }}"#, extra));
+    let dir = tmp.path();
+
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    fs::write(dir.join("votes/vote-1.json"), signed_vote("bob", "red", "2025-06-22T23:55:41Z")).unwrap();
+    fs::write(dir.join("votes/vote-2.json"), signed_vote("alice", "blue", "2025-06-22T09:00:00Z")).unwrap();
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    tmp
+}
+
+#[test]
+fn earliest_vote_breaks_the_tie() {
+    let tmp = tied_election(r#", "tie_break": "earliest_vote""#);
+
+    let (code, out) = gitvote(tmp.path(), &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("Tie between blue, red broken by earliest vote: red (block 0 by bob"), "{}", out);
+    // The verdict judges the tie-broken winner on its own support
+    assert!(out.contains("Support: red 1 of 2 (50.0%)"), "{}", out);
+}
+
+#[test]
+fn earliest_vote_goes_by_merge_order_not_commit_dates() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"], "tie_break": "earliest_vote" }"#);
+    let dir = tmp.path();
+    as_voter(dir, "admin", "admin@example.com");
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    let main = git_output(dir, &["rev-parse", "--abbrev-ref", "HEAD"]);
+
+    // bob's vote is committed earlier, but alice's is merged first
+    git(dir, &["checkout", "-q", "-b", "bob"]);
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    fs::write(dir.join("votes/vote-b.json"), signed_vote("bob", "red", "2025-06-10T00:00:00Z")).unwrap();
+    commit_at(dir, "2025-06-10T00:00:00Z");
+    git(dir, &["checkout", "-q", &main]);
+    git(dir, &["checkout", "-q", "-b", "alice"]);
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    fs::write(dir.join("votes/vote-a.json"), signed_vote("alice", "blue", "2025-06-20T00:00:00Z")).unwrap();
+    commit_at(dir, "2025-06-20T00:00:00Z");
+    git(dir, &["checkout", "-q", &main]);
+    git(dir, &["merge", "-q", "--no-ff", "-m", "merge alice", "alice"]);
+    git(dir, &["merge", "-q", "--no-ff", "-m", "merge bob", "bob"]);
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("broken by earliest vote: blue (block 0 by alice"), "{}", out);
+}
+
+#[test]
+fn lot_is_seeded_by_the_chain_head() {
+    let tmp = tied_election(r#", "tie_break": "lot""#);
+    let dir = tmp.path();
+
+    let head: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("blocks/block-0001.json")).unwrap()).unwrap();
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("broken by lot: "), "{}", out);
+    assert!(out.contains(&format!("with seed {}", head["hash"].as_str().unwrap())), "{}", out);
+
+    // Anyone recounting draws the same lot
+    assert_eq!(gitvote(dir, &["tally"]).1, out);
+}
+
+#[test]
+fn casting_vote_must_come_from_the_casting_voter() {
+    let tmp = tied_election(r#", "tie_break": "casting_vote", "casting_voter": "chair""#);
+    let dir = tmp.path();
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("not broken by casting vote: awaiting a casting vote from chair"), "{}", out);
+    assert!(out.contains("Reason: tie between blue, red"), "{}", out);
+
+    fs::write(dir.join("casting-vote.json"), signed_vote("mallory", "red", "2025-06-23T10:00:00Z")).unwrap();
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("not the casting voter chair"), "{}", out);
+
+    fs::write(dir.join("casting-vote.json"), signed_vote("chair", "red", "2025-06-23T10:00:00Z")).unwrap();
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("broken by casting vote: red (cast by chair"), "{}", out);
+    assert!(out.contains("Support: red 1 of 2 (50.0%)"), "{}", out);
+}
+
+#[test]
+fn each_question_takes_its_own_casting_vote() {
+    let tmp = election(
+        r#"{ "questions": [
+            { "id": "q1", "allowed": ["yes", "no"], "tie_break": "casting_vote", "casting_voter": "chair <chair@example.com>" },
+            { "id": "q2", "allowed": ["a", "b"], "tie_break": "casting_vote", "casting_voter": "chair <chair@example.com>" }
+        ] }"#,
+    );
+    let dir = tmp.path();
+    as_voter(dir, "admin", "admin@example.com");
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    for (voter, q1, q2) in [("alice", "yes", "a"), ("bob", "no", "b")] {
+        as_voter(dir, voter, &format!("{}@example.com", voter));
+        let (code, out) = gitvote(dir, &["cast", "--answer", &format!("q1={}", q1), "--answer", &format!("q2={}", q2)]);
+        assert_eq!(code, 0, "{}", out);
+    }
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    as_voter(dir, "chair", "chair@example.com");
+    assert_eq!(gitvote(dir, &["casting-vote", "--choice", "yes"]).0, 2);
+    assert_eq!(gitvote(dir, &["casting-vote", "--question", "q9", "--choice", "yes"]).0, 2);
+    assert_eq!(gitvote(dir, &["casting-vote", "--question", "q2", "--choice", "yes"]).0, 5);
+    let (code, out) = gitvote(dir, &["casting-vote", "--question", "q2", "--choice", "b"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("casting-vote-q2.json"), "{}", out);
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("Tie between yes, no not broken by casting vote: awaiting a casting vote from chair <chair@example.com> in casting-vote-q1.json"), "{}", out);
+    assert!(out.contains("Tie between a, b broken by casting vote: b (cast by chair"), "{}", out);
+}
@@ -1,4 +1,5 @@
 use gitvote::block::Block;
+use gitvote::vote::Ballot;
 use chrono::Utc;
 use sha2::{Digest, Sha256};
 
@@ -11,9 +12,17 @@ fn test_valid_chain() {
         let block = Block {
             index: i,
             timestamp: Utc::now(),
-            choice: if i % 2 == 0 { "blue".to_string() } else { "red".to_string() },
+            ballot: Ballot::Choice(if i % 2 == 0 { "blue".to_string() } else { "red".to_string() }),
             voter: format!("voter-{}", i),
             prev_hash: prev_hash.clone(),
+            hash: String::new(),
+            signature: String::new(),
+            weight: None,
+            superseded_by: None,
+            supersedes: None,
+            withdraws: None,
+            delegation: None,
+            recorded_at: None,
         };
 
         let raw_json = serde_json::to_string(&block).unwrap();
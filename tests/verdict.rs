@@ -0,0 +1,140 @@
+mod common;
+
+use gitvote::methods;
+use gitvote::schema::Schema;
+use gitvote::verdict::{decide, Status, Verdict};
+use gitvote::vote::Ballot;
+use common::{blocks, schema_error};
+
+fn verdict(schema: &str, choices: &[&str]) -> Verdict {
+    verdict_of(schema, choices.iter().map(|c| Ballot::Choice(c.to_string())).collect())
+}
+
+fn verdict_of(schema: &str, ballots: Vec<Ballot>) -> Verdict {
+    let schema: Schema = serde_json::from_str(schema).unwrap();
+    let blocks = blocks(&schema, ballots);
+    let outcome = methods::tally(&schema, &blocks).unwrap();
+    decide(&schema, &blocks, &outcome).unwrap()
+}
+
+#[test]
+fn majority_is_taken_with_or_without_abstentions() {
+    let choices = ["yes", "yes", "yes", "yes", "no", "abstain", "abstain"];
+
+    // 4 of 5 once the abstentions are left out
+    let excluded = verdict(
+        r#"{ "allowed": ["yes", "no", "abstain"], "majority": "two_thirds", "abstain": "abstain" }"#,
+        &choices,
+    );
+    assert_eq!(excluded.status, Status::Passed);
+    assert_eq!(excluded.abstentions, 2);
+    assert!(excluded.to_string().contains("Support: yes 4 of 5 (80.0%)"));
+
+    // 4 of 7 falls short of two thirds once they count against
+    let against = verdict(
+        r#"{ "allowed": ["yes", "no", "abstain"], "majority": "two_thirds", "abstain": "abstain", "abstentions": "against" }"#,
+        &choices,
+    );
+    assert_eq!(against.status, Status::Failed);
+
+    let unanimous = verdict(r#"{ "allowed": ["yes", "no"], "majority": "unanimous" }"#, &["yes", "yes", "no"]);
+    assert_eq!(unanimous.status, Status::Failed);
+}
+
+#[test]
+fn quorum_can_be_a_fraction_of_the_roll() {
+    let schema = r#"{ "allowed": ["yes", "no"], "voters": ["a", "b", "c", "d", "e"], "quorum": { "fraction": 0.5 } }"#;
+
+    let short = verdict(schema, &["yes", "yes"]);
+    assert_eq!(short.status, Status::NoQuorum);
+    assert_eq!(short.quorum, Some(3));
+    assert!(short.to_string().starts_with("Verdict: NO QUORUM\n"));
+
+    assert_eq!(verdict(schema, &["yes", "yes", "no"]).status, Status::Passed);
+    assert_eq!(
+        verdict(r#"{ "allowed": ["yes", "no"], "quorum": { "voters": 4 } }"#, &["yes", "yes", "no"]).status,
+        Status::NoQuorum
+    );
+
+    for fraction in ["0", "1.5", "-0.5"] {
+        let out = schema_error(&format!(r#"{{ "allowed": ["yes", "no"], "voters": ["a"], "quorum": {{ "fraction": {} }} }}"#, fraction));
+        assert!(out.contains("A fractional quorum must be above 0 and at most 1"), "{}", out);
+    }
+}
+
+#[test]
+fn ties_and_empty_results_fail() {
+    let tie = verdict(r#"{ "allowed": ["yes", "no"] }"#, &["yes", "no"]);
+    assert_eq!(tie.status, Status::Failed);
+    assert!(tie.reason.contains("tie between yes, no"));
+
+    // Abstentions are not candidates, so nobody wins
+    let abstained = verdict(r#"{ "allowed": ["yes", "abstain"], "abstain": "abstain" }"#, &["abstain", "abstain"]);
+    assert_eq!(abstained.status, Status::Failed);
+    assert_eq!(abstained.reason, "there is no winner");
+
+    // Too few options to fill every seat is not a tie
+    let rank = |options: &[&str]| Ballot::Ranking(options.iter().map(|s| s.to_string()).collect());
+    let unfilled = verdict_of(r#"{ "allowed": ["a", "b"], "method": "stv", "seats": 3 }"#, vec![rank(&["a"]), rank(&["b", "a"])]);
+    assert_eq!(unfilled.status, Status::Failed);
+    assert_eq!(unfilled.reason, "only 2 of 3 seats filled");
+}
+
+#[test]
+fn ranked_winners_are_not_held_to_first_preferences() {
+    let rank = |options: &[&str]| Ballot::Ranking(options.iter().map(|s| s.to_string()).collect());
+    let mut ballots = vec![rank(&["a"]); 4];
+    ballots.extend(vec![rank(&["b"]); 3]);
+    ballots.extend(vec![rank(&["c", "b"]); 2]);
+
+    // b has 3 first preferences of 9 but 5 in the final round
+    let result = verdict_of(r#"{ "allowed": ["a", "b", "c"], "method": "irv" }"#, ballots);
+    assert_eq!(result.status, Status::Passed);
+    assert_eq!(result.support, None);
+    assert!(result.reason.starts_with("b wins"), "{}", result.reason);
+}
+
+#[test]
+fn blank_ballots_count_for_quorum_only() {
+    let schema = r#"{ "allowed": ["yes", "no", "blank"], "blank": "blank", "abstentions": "against", "quorum": { "voters": 4 } }"#;
+
+    // 2 of 3 once the blank ballot is set aside, even with abstentions
+    // counted against
+    let result = verdict(schema, &["yes", "yes", "no", "blank"]);
+    assert_eq!(result.status, Status::Passed);
+    assert_eq!(result.blanks, 1);
+    assert_eq!(result.support.unwrap().of, 3);
+
+    // Even a schema naming one option for both is counted without underflow
+    let same = verdict(r#"{ "allowed": ["yes", "skip"], "abstain": "skip", "blank": "skip" }"#, &["yes", "skip", "skip"]);
+    assert_eq!(same.abstentions, 2);
+    assert_eq!(same.blanks, 2);
+    assert_eq!(same.status, Status::Failed);
+}
+
+#[test]
+fn none_of_the_above_winning_means_a_rerun() {
+    let schema = r#"{ "allowed": ["ann", "ben", "nobody"], "nota": "nobody" }"#;
+
+    let result = verdict(schema, &["nobody", "nobody", "ann"]);
+    assert_eq!(result.status, Status::Rerun);
+    assert!(result.to_string().starts_with("Verdict: RE-RUN\n"));
+
+    assert_eq!(verdict(schema, &["nobody", "ann", "ann"]).status, Status::Passed);
+}
+
+#[test]
+fn abstain_and_blank_stand_alone_and_are_not_counted() {
+    let schema: Schema = serde_json::from_str(
+        r#"{ "allowed": ["a", "b", "abstain", "none"], "method": "irv", "abstain": "abstain", "nota": "none" }"#,
+    ).unwrap();
+    let rank = |options: &[&str]| Ballot::Ranking(options.iter().map(|s| s.to_string()).collect());
+
+    assert!(methods::check_ballot(&schema, &rank(&["abstain"])).is_ok());
+    assert!(methods::check_ballot(&schema, &rank(&["a", "none", "b"])).is_ok());
+    assert_eq!(methods::check_ballot(&schema, &rank(&["abstain", "a"])).unwrap_err().rule, "invalid-ranking");
+
+    let plurality: Schema = serde_json::from_str(r#"{ "allowed": ["a", "abstain"], "abstain": "abstain" }"#).unwrap();
+    let outcome = methods::tally(&plurality, &blocks(&plurality, vec![Ballot::Choice("abstain".into())])).unwrap();
+    assert_eq!(outcome.text, "a votes: 0\n");
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::fs;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+use common::{election, gitvote, schema_error, write_vote};
+
+fn weighted_election(weights: &str, pin: Option<&str>) -> TempDir {
+    let pinned = pin.map(|p| format!(r#", "sha256": "{}""#, p)).unwrap_or_default();
+    let tmp = election(&format!(r#"{{ "allowed": ["blue", "red"], "weights": {{ "file": "weights.json"{} }} }}"#, pinned));
+    let dir = tmp.path();
+    fs::write(dir.join("weights.json"), weights).unwrap();
+
+    write_vote(dir, "alice", "blue");
+    write_vote(dir, "bob", "red");
+    write_vote(dir, "carol", "red");
+    tmp
+}
+
+#[test]
+fn tally_sums_weights_recorded_in_blocks() {
+    let weights = r#"{ "alice": 5, "bob": 2 }"#;
+    let pin = format!("{:x}", Sha256::digest(weights.as_bytes()));
+    let tmp = weighted_election(weights, Some(&pin));
+    let dir = tmp.path();
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    let block: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("blocks/block-0000.json")).unwrap()).unwrap();
+    assert_eq!(block["weight"], 5);
+
+    // carol is not in the ledger and weighs the default 1
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 5\nred votes: 3\n"), "{}", out);
+    assert!(out.contains("  carol: 1\n"), "{}", out);
+    assert!(out.contains("Total weight: 8\n"), "{}", out);
+
+    // Rebuilding keeps the chain valid
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+}
+
+#[test]
+fn weights_file_is_pinned_at_open() {
+    let tmp = weighted_election(r#"{ "alice": 5 }"#, Some("00"));
+
+    let (code, out) = gitvote(tmp.path(), &["build-chain"]);
+    assert_eq!(code, 4);
+    assert!(out.contains("changed since the vote opened"), "{}", out);
+}
+
+#[test]
+fn weights_above_the_maximum_are_refused() {
+    let out = schema_error(r#"{ "allowed": ["yes", "no"], "weights": { "alice": 2000000000 } }"#);
+    assert!(out.contains("Weight 2000000000 for alice is above the maximum of 1000000000"), "{}", out);
+
+    let out = schema_error(r#"{ "allowed": ["yes", "no"], "weights": { "alice": 1 }, "default_weight": 18446744073709551615 }"#);
+    assert!(out.contains("for default_weight is above the maximum"), "{}", out);
+
+    let tmp = weighted_election(r#"{ "alice": 1000000001 }"#, None);
+    let (code, out) = gitvote(tmp.path(), &["build-chain"]);
+    assert_eq!(code, 4, "{}", out);
+    assert!(out.contains("for alice is above the maximum"), "{}", out);
+}
+
+#[test]
+fn unweighted_blocks_keep_their_old_shape() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+    write_vote(dir, "alice", "blue");
+
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    let block = fs::read_to_string(dir.join("blocks/block-0000.json")).unwrap();
+    assert!(!block.contains("weight"));
+    assert!(!gitvote(dir, &["tally"]).1.contains("Weights:"));
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use gitvote::utils::generate_fake_signature;
+use tempfile::TempDir;
+use common::{as_voter, backdate_chain, cast, commit_at, election, gitvote, signed_vote};
+
+/// alice votes blue and bob red.
+fn voted(schema: &str) -> TempDir {
+    let tmp = election(schema);
+    let dir = tmp.path();
+    cast(dir, "alice", "blue");
+    cast(dir, "bob", "red");
+    tmp
+}
+
+/// Path of the vote file cast by `voter`.
+fn vote_of(dir: &Path, voter: &str) -> String {
+    fs::read_dir(dir.join("votes"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| fs::read_to_string(p).unwrap().contains(voter))
+        .map(|p| format!("votes/{}", p.file_name().unwrap().to_string_lossy()))
+        .unwrap()
+}
+
+fn write_withdrawal(dir: &Path, voter: &str, vote: &str, timestamp: &str) {
+    fs::create_dir_all(dir.join("withdrawals")).unwrap();
+    let record = serde_json::json!({
+        "voter": voter,
+        "vote": vote,
+        "signature": generate_fake_signature(voter, &format!("withdraw:{}", vote)),
+        "timestamp": timestamp,
+    });
+    fs::write(dir.join("withdrawals/withdrawal-manual.json"), record.to_string()).unwrap();
+}
+
+#[test]
+fn withdrawn_vote_is_kept_in_the_chain_but_not_counted() {
+    let tmp = voted(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    as_voter(dir, "alice", "alice@example.com");
+    let (code, out) = gitvote(dir, &["withdraw"]);
+    assert_eq!(code, 0, "{}", out);
+    assert_eq!(gitvote(dir, &["withdraw"]).0, 5);
+
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    // The vote's block is untouched; the withdrawal follows as its own block
+    let block = |n: usize| -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(dir.join(format!("blocks/block-{:04}.json", n))).unwrap()).unwrap()
+    };
+    assert_eq!(block(0)["choice"], "blue");
+    assert!(block(0).get("withdraws").is_none());
+    assert_eq!(block(2)["withdraws"], 0);
+    assert_eq!(block(2)["choice"], "blue");
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 0\nred votes: 1\n"), "{}", out);
+    assert!(out.contains("  block 0 by alice <alice@example.com> (blue), withdrawn at "), "{}", out);
+    assert!(out.contains(" in block 2\n"), "{}", out);
+
+    // Having withdrawn, alice may vote again
+    cast(dir, "alice", "red");
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+}
+
+#[test]
+fn only_the_original_voter_can_withdraw() {
+    let tmp = voted(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    let alices = vote_of(dir, "alice");
+    write_withdrawal(dir, "bob <bob@example.com>", &alices, "2025-06-22T10:00:00Z");
+
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("Only alice <alice@example.com> can withdraw"), "{}", out);
+
+    // The forged withdrawal is ignored when counting
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (_, out) = gitvote(dir, &["tally"]);
+    assert!(out.contains("blue votes: 1\nred votes: 1\n"), "{}", out);
+}
+
+#[test]
+fn withdrawals_must_fall_inside_the_voting_window() {
+    let tmp = election(r#"{ "allowed": ["blue", "red"] }"#);
+    let dir = tmp.path();
+
+    // Both votes were recorded inside the window, then the vote closed
+    as_voter(dir, "alice", "alice@example.com");
+    fs::create_dir_all(dir.join("votes")).unwrap();
+    for (voter, choice) in [("alice", "blue"), ("bob", "red")] {
+        let vote = signed_vote(&format!("{0} <{0}@example.com>", voter), choice, "2025-06-20T00:00:00Z");
+        fs::write(dir.join(format!("votes/vote-{}.json", voter)), vote).unwrap();
+    }
+    commit_at(dir, "2025-06-20T00:00:00Z");
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    backdate_chain(dir, "2025-06-20T00:00:00Z");
+    fs::write(dir.join("schema.json"), r#"{ "allowed": ["blue", "red"], "closes_at": "2025-06-30T00:00:00Z" }"#).unwrap();
+
+    let (code, out) = gitvote(dir, &["withdraw"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("only be withdrawn while voting is open"), "{}", out);
+
+    // A backdated timestamp does not help: a withdrawal the chain has not
+    // recorded is made now
+    let alices = vote_of(dir, "alice");
+    write_withdrawal(dir, "alice <alice@example.com>", &alices, "2025-06-29T00:00:00Z");
+    let (code, out) = gitvote(dir, &["validate"]);
+    assert_eq!(code, 5, "{}", out);
+    assert!(out.contains("outside the voting window"), "{}", out);
+
+    // Nor does a backdated commit, and the chain leaves it out
+    commit_at(dir, "2025-06-29T00:00:00Z");
+    assert_eq!(gitvote(dir, &["validate"]).0, 5);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    let (_, out) = gitvote(dir, &["tally"]);
+    assert!(out.contains("blue votes: 1\nred votes: 1\n"), "{}", out);
+
+    // Once recorded while voting was open, it counts, whatever its
+    // timestamp says
+    write_withdrawal(dir, "alice <alice@example.com>", &alices, "2025-07-01T00:00:00Z");
+    fs::write(dir.join("schema.json"), r#"{ "allowed": ["blue", "red"] }"#).unwrap();
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+    backdate_chain(dir, "2025-06-29T00:00:00Z");
+    fs::write(dir.join("schema.json"), r#"{ "allowed": ["blue", "red"], "closes_at": "2025-06-30T00:00:00Z" }"#).unwrap();
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("blue votes: 0\nred votes: 1\n"), "{}", out);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use gitvote::methods::check_ballot;
+use gitvote::schema::Schema;
+use gitvote::vote::Ballot;
+use gitvote::writein::normalize;
+use common::{election, gitvote, write_vote};
+
+#[test]
+fn names_are_folded_trimmed_and_composed() {
+    assert_eq!(normalize("Alice"), "alice");
+    assert_eq!(normalize("  ALICE "), "alice");
+    assert_eq!(normalize("Mary \t Ann"), "mary ann");
+    // e + combining acute accent composes to é
+    assert_eq!(normalize("Rene\u{301}e"), normalize("Ren\u{e9}e"));
+    // Full case folding, not just lowercasing
+    assert_eq!(normalize("STRASSE"), normalize("straße"));
+    assert_eq!(normalize("ΣΊΣΥΦΟΣ"), normalize("σίσυφος"));
+}
+
+#[test]
+fn write_ins_pass_validation_only_when_allowed() {
+    let open: Schema = serde_json::from_str(r#"{ "allowed": ["bob"], "method": "irv", "write_ins": true }"#).unwrap();
+    let closed: Schema = serde_json::from_str(r#"{ "allowed": ["bob"], "method": "irv" }"#).unwrap();
+    let ranking = |names: &[&str]| Ballot::Ranking(names.iter().map(|s| s.to_string()).collect());
+
+    assert!(check_ballot(&open, &ranking(&["Alice", "bob"])).is_ok());
+    assert!(check_ballot(&closed, &ranking(&["Alice", "bob"])).is_err());
+
+    // The same write-in twice, or a listed option in other case, is a duplicate
+    assert!(check_ballot(&open, &ranking(&["Alice", " alice "])).is_err());
+    assert!(check_ballot(&open, &ranking(&["BOB", "bob"])).is_err());
+    assert!(check_ballot(&open, &ranking(&["  "])).is_err());
+}
+
+#[test]
+fn tally_merges_spellings_and_lists_write_ins() {
+    let tmp = election(r#"{ "allowed": ["bob"], "write_ins": true }"#);
+    let dir = tmp.path();
+
+    write_vote(dir, "v1", "Alice");
+    write_vote(dir, "v2", " alice ");
+    write_vote(dir, "v3", "ALICE");
+    write_vote(dir, "v4", "Bob");
+    write_vote(dir, "v5", "Carol");
+
+    assert_eq!(gitvote(dir, &["validate"]).0, 0);
+    assert_eq!(gitvote(dir, &["build-chain"]).0, 0);
+
+    let (code, out) = gitvote(dir, &["tally"]);
+    assert_eq!(code, 0, "{}", out);
+    assert!(out.contains("bob votes: 1\nalice votes: 3\ncarol votes: 1\n"), "{}", out);
+    assert!(out.contains("Write-ins:\n  alice: named on 3 ballot(s), as \" alice \", \"ALICE\", \"Alice\"\n"), "{}", out);
+    assert!(out.contains("  carol: named on 1 ballot(s), as \"Carol\"\n"), "{}", out);
+    assert!(out.contains("Support: alice 3 of 5"), "{}", out);
+}